@@ -0,0 +1,256 @@
+use core::ops::Add;
+use core::ops::Div;
+use core::ops::Mul;
+use core::ops::Neg;
+use core::ops::Sub;
+
+use crate::pbrt::{next_float_down, next_float_up, Float, MACHINE_EPSILON};
+
+// a float together with an interval [low, high] guaranteed to contain the
+// value exact arithmetic would have produced; every operation rounds the
+// interval outward by one ulp so it stays conservative
+#[derive(Debug, Default, Copy, Clone)]
+pub struct EFloat {
+    pub v: Float,
+    pub low: Float,
+    pub high: Float,
+}
+
+impl EFloat {
+    pub fn new(v: Float, err: Float) -> Self {
+        if err == 0.0 {
+            Self { v, low: v, high: v }
+        } else {
+            Self {
+                v,
+                low: next_float_down(v - err),
+                high: next_float_up(v + err),
+            }
+        }
+    }
+
+    pub fn lower_bound(&self) -> Float {
+        self.low
+    }
+
+    pub fn upper_bound(&self) -> Float {
+        self.high
+    }
+
+    pub fn absolute_error(&self) -> Float {
+        next_float_up((self.high - self.v).abs().max((self.v - self.low).abs()))
+    }
+
+    pub fn sqrt(&self) -> EFloat {
+        Self {
+            v: self.v.sqrt(),
+            low: next_float_down(self.low.sqrt()),
+            high: next_float_up(self.high.sqrt()),
+        }
+    }
+
+    pub fn abs(&self) -> EFloat {
+        if self.low >= 0.0 {
+            *self
+        } else if self.high <= 0.0 {
+            -*self
+        } else {
+            Self {
+                v: self.v.abs(),
+                low: 0.0,
+                high: (-self.low).max(self.high),
+            }
+        }
+    }
+
+    // roots of a t^2 + b t + c with error bounds, t0 <= t1; false when the
+    // discriminant is negative. The discriminant is formed in f64 and q avoids
+    // the cancellation of the textbook formula
+    pub fn quadratic(a: EFloat, b: EFloat, c: EFloat, t0: &mut EFloat, t1: &mut EFloat) -> bool {
+        let discrim = b.v as f64 * b.v as f64 - 4.0 * a.v as f64 * c.v as f64;
+        if discrim < 0.0 {
+            return false;
+        }
+        let root_discrim = discrim.sqrt() as Float;
+        let float_root_discrim = EFloat::new(root_discrim, MACHINE_EPSILON * root_discrim);
+        let q = if b.v < 0.0 {
+            EFloat::from(-0.5) * (b - float_root_discrim)
+        } else {
+            EFloat::from(-0.5) * (b + float_root_discrim)
+        };
+        *t0 = q / a;
+        *t1 = c / q;
+        if t0.v > t1.v {
+            std::mem::swap(t0, t1);
+        }
+        true
+    }
+}
+
+impl From<Float> for EFloat {
+    fn from(v: Float) -> Self {
+        Self::new(v, 0.0)
+    }
+}
+
+impl From<EFloat> for Float {
+    fn from(e: EFloat) -> Self {
+        e.v
+    }
+}
+
+impl Add for EFloat {
+    type Output = EFloat;
+
+    fn add(self, rhs: EFloat) -> EFloat {
+        EFloat {
+            v: self.v + rhs.v,
+            low: next_float_down(self.low + rhs.low),
+            high: next_float_up(self.high + rhs.high),
+        }
+    }
+}
+
+impl Sub for EFloat {
+    type Output = EFloat;
+
+    fn sub(self, rhs: EFloat) -> EFloat {
+        EFloat {
+            v: self.v - rhs.v,
+            low: next_float_down(self.low - rhs.high),
+            high: next_float_up(self.high - rhs.low),
+        }
+    }
+}
+
+impl Mul for EFloat {
+    type Output = EFloat;
+
+    fn mul(self, rhs: EFloat) -> EFloat {
+        let prod = [
+            self.low * rhs.low,
+            self.high * rhs.low,
+            self.low * rhs.high,
+            self.high * rhs.high,
+        ];
+        EFloat {
+            v: self.v * rhs.v,
+            low: next_float_down(prod.iter().cloned().fold(Float::INFINITY, Float::min)),
+            high: next_float_up(prod.iter().cloned().fold(Float::NEG_INFINITY, Float::max)),
+        }
+    }
+}
+
+impl Div for EFloat {
+    type Output = EFloat;
+
+    // a divisor interval straddling zero leaves the quotient unbounded
+    fn div(self, rhs: EFloat) -> EFloat {
+        if rhs.low < 0.0 && rhs.high > 0.0 {
+            return EFloat {
+                v: self.v / rhs.v,
+                low: Float::NEG_INFINITY,
+                high: Float::INFINITY,
+            };
+        }
+        let quot = [
+            self.low / rhs.low,
+            self.high / rhs.low,
+            self.low / rhs.high,
+            self.high / rhs.high,
+        ];
+        EFloat {
+            v: self.v / rhs.v,
+            low: next_float_down(quot.iter().cloned().fold(Float::INFINITY, Float::min)),
+            high: next_float_up(quot.iter().cloned().fold(Float::NEG_INFINITY, Float::max)),
+        }
+    }
+}
+
+impl Neg for EFloat {
+    type Output = EFloat;
+
+    fn neg(self) -> EFloat {
+        EFloat {
+            v: -self.v,
+            low: -self.high,
+            high: -self.low,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EFloat;
+    use crate::pbrt::Float;
+
+    fn contains(e: &EFloat, exact: f64) -> bool {
+        e.lower_bound() as f64 <= exact && exact <= e.upper_bound() as f64
+    }
+
+    #[test]
+    pub fn test_chain_contains_exact() {
+        let (a, b, c, d, e): (Float, Float, Float, Float, Float) = (0.1, 3.7, -2.3, 1e-3, 7.9);
+        let ea = EFloat::new(a, 0.0);
+        let r = ((ea + EFloat::from(b)) * EFloat::from(c) - EFloat::from(d)) / EFloat::from(e);
+        let exact = ((a as f64 + b as f64) * c as f64 - d as f64) / e as f64;
+        assert!(contains(&r, exact));
+        let s = r.abs().sqrt();
+        assert!(contains(&s, exact.abs().sqrt()));
+        assert!(s.absolute_error() > 0.0);
+        assert_eq!(Float::from(s), s.v);
+    }
+
+    #[test]
+    pub fn test_repeated_accumulation_contains_exact() {
+        let mut sum = EFloat::from(0.0);
+        let mut exact = 0.0f64;
+        for i in 1..1000 {
+            let x = 1.0 / i as Float;
+            sum = sum + EFloat::from(x) * EFloat::from(x);
+            exact += x as f64 * x as f64;
+        }
+        assert!(contains(&sum, exact));
+    }
+
+    #[test]
+    pub fn test_input_error_widens() {
+        let e = EFloat::new(2.0, 0.5);
+        assert!(e.lower_bound() <= 1.5 && e.upper_bound() >= 2.5);
+        let neg = -e;
+        assert_eq!(neg.lower_bound(), -e.upper_bound());
+        let abs = EFloat::new(0.0, 1.0).abs();
+        assert_eq!(abs.lower_bound(), 0.0);
+        assert!(abs.upper_bound() >= 1.0);
+    }
+
+    #[test]
+    pub fn test_div_by_interval_with_zero() {
+        let r = EFloat::from(1.0) / EFloat::new(0.0, 1e-3);
+        assert_eq!(r.lower_bound(), Float::NEG_INFINITY);
+        assert_eq!(r.upper_bound(), Float::INFINITY);
+    }
+
+    #[test]
+    pub fn test_quadratic() {
+        let mut t0 = EFloat::default();
+        let mut t1 = EFloat::default();
+        assert!(EFloat::quadratic(
+            EFloat::from(1.0),
+            EFloat::from(-3.0),
+            EFloat::from(2.0),
+            &mut t0,
+            &mut t1
+        ));
+        assert!(t0.v <= t1.v);
+        assert!(contains(&t0, 1.0));
+        assert!(contains(&t1, 2.0));
+        assert!(!EFloat::quadratic(
+            EFloat::from(1.0),
+            EFloat::from(0.0),
+            EFloat::from(1.0),
+            &mut t0,
+            &mut t1
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::pbrt::Float;
+
+// the adjacent representable value towards +inf; both zeros step to the
+// smallest positive subnormal, +inf and NaN are returned unchanged
+pub fn next_float_up(v: Float) -> Float {
+    if v.is_nan() || (v.is_infinite() && v > 0.0) {
+        return v;
+    }
+    let v = if v == -0.0 { 0.0 } else { v };
+    let bits = v.to_bits();
+    Float::from_bits(if v >= 0.0 { bits + 1 } else { bits - 1 })
+}
+
+// mirror of next_float_up towards -inf
+pub fn next_float_down(v: Float) -> Float {
+    if v.is_nan() || (v.is_infinite() && v < 0.0) {
+        return v;
+    }
+    let v = if v == 0.0 { -0.0 } else { v };
+    let bits = v.to_bits();
+    Float::from_bits(if v > 0.0 { bits - 1 } else { bits + 1 })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::Float;
+
+    #[test]
+    pub fn test_next_float_zero() {
+        assert!(super::next_float_up(0.0) > 0.0);
+        assert!(super::next_float_up(-0.0) > 0.0);
+        assert!(super::next_float_down(0.0) < 0.0);
+        assert!(super::next_float_down(-0.0) < 0.0);
+    }
+
+    #[test]
+    pub fn test_next_float_round_trip() {
+        for &x in &[1.0, -1.0, 0.1, 3.5e-8, -2.5e20, Float::MAX, -Float::MAX] {
+            let up = super::next_float_up(x);
+            assert!(up > x);
+            assert_eq!(super::next_float_down(up), x);
+            let down = super::next_float_down(x);
+            assert!(down < x);
+            assert_eq!(super::next_float_up(down), x);
+        }
+    }
+
+    #[test]
+    pub fn test_next_float_infinity_nan() {
+        assert_eq!(super::next_float_up(Float::INFINITY), Float::INFINITY);
+        assert_eq!(
+            super::next_float_down(Float::NEG_INFINITY),
+            Float::NEG_INFINITY
+        );
+        assert_eq!(super::next_float_down(Float::INFINITY), Float::MAX);
+        assert_eq!(super::next_float_up(Float::NEG_INFINITY), -Float::MAX);
+        assert!(super::next_float_up(Float::NAN).is_nan());
+        assert!(super::next_float_down(Float::NAN).is_nan());
+    }
+}
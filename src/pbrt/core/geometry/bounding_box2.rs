@@ -1,6 +1,7 @@
+use std::hash::{Hash, Hasher};
 use std::ops::Index;
 
-use crate::pbrt::{lerp, Float, Int, Point2, Point2f, Scalar, Vector2, Zero};
+use crate::pbrt::{BoundsAxes, Float, Int, Lerp, Point2, Point2f, Scalar, Vector2, Vector2f, Zero};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Bounds2<T: Scalar> {
@@ -43,11 +44,15 @@ impl<T: Scalar> Bounds2<T> {
 
     pub fn lerp(&self, t: &Point2f) -> Point2<T> {
         Point2::<T>::new(
-            T::from_float(lerp(t.x, self.p_min.x.to_float(), self.p_max.x.to_float())),
-            T::from_float(lerp(t.y, self.p_min.y.to_float(), self.p_max.y.to_float())),
+            T::from_float(self.p_min.x.to_float().lerp(self.p_max.x.to_float(), t.x)),
+            T::from_float(self.p_min.y.to_float().lerp(self.p_max.y.to_float(), t.y)),
         )
     }
 
+    pub fn lerp_vec(&self, t: &Vector2f) -> Point2<T> {
+        self.lerp(&Point2f::from(*t))
+    }
+
     pub fn offset(&self, p: &Point2<T>) -> Vector2<T> {
         let mut o = *p - self.p_min;
         if self.p_max.x > self.p_min.x {
@@ -67,6 +72,49 @@ impl<T: Scalar> Bounds2<T> {
         p.x >= b.p_min.x && p.x < b.p_max.x && p.y >= b.p_min.y && p.y < b.p_max.y
     }
 
+    pub fn intersect(b1: &Bounds2<T>, b2: &Bounds2<T>) -> Bounds2<T> {
+        Self {
+            p_min: Point2::<T>::new(
+                T::max(b1.p_min.x, b2.p_min.x),
+                T::max(b1.p_min.y, b2.p_min.y),
+            ),
+            p_max: Point2::<T>::new(
+                T::min(b1.p_max.x, b2.p_max.x),
+                T::min(b1.p_max.y, b2.p_max.y),
+            ),
+        }
+    }
+
+    pub fn expand(&self, delta: T) -> Bounds2<T> {
+        let d = Vector2::<T>::new(delta, delta);
+        Self {
+            p_min: self.p_min - d,
+            p_max: self.p_max + d,
+        }
+    }
+
+    pub fn overlaps(a: &Bounds2<T>, b: &Bounds2<T>) -> bool {
+        let x = a.p_max.x >= b.p_min.x && a.p_min.x <= b.p_max.x;
+        let y = a.p_max.y >= b.p_min.y && a.p_min.y <= b.p_max.y;
+        x && y
+    }
+
+    pub fn corner(&self, i: Int) -> Point2<T> {
+        Point2::<T>::new(self[i & 1].x, self[if i & 2 != 0 { 1 } else { 0 }].y)
+    }
+
+    // to the closest point of the box, zero when p is inside
+    pub fn distance_squared(b: &Bounds2<T>, p: &Point2<T>) -> Float {
+        let gap = |v: T, lo: T, hi: T| T::max(T::max(lo - v, v - hi), T::zero()).to_float();
+        let dx = gap(p.x, b.p_min.x, b.p_max.x);
+        let dy = gap(p.y, b.p_min.y, b.p_max.y);
+        dx * dx + dy * dy
+    }
+
+    pub fn distance(b: &Bounds2<T>, p: &Point2<T>) -> Float {
+        Bounds2::distance_squared(b, p).sqrt()
+    }
+
     pub fn bounding_sphere(&self, c: &mut Point2<T>, rad: &mut Float) {
         *c = (self.p_min + self.p_max) / T::from_float(2.0); // todo: should this be '2.0 as Float'
         *rad = if Bounds2::inside(c, self) {
@@ -75,6 +123,82 @@ impl<T: Scalar> Bounds2<T> {
             Float::zero()
         }
     }
+
+    pub fn bounding_sphere_tuple(&self) -> (Point2<T>, Float) {
+        let mut c = Point2::<T>::default();
+        let mut rad = Float::zero();
+        self.bounding_sphere(&mut c, &mut rad);
+        (c, rad)
+    }
+}
+
+impl Bounds2<Float> {
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_pixel_bounds(&self, film: &Bounds2<Int>) -> Bounds2<Int> {
+        let pixels = Bounds2::<Int> {
+            p_min: Point2::new(self.p_min.x.floor() as Int, self.p_min.y.floor() as Int),
+            p_max: Point2::new(self.p_max.x.ceil() as Int, self.p_max.y.ceil() as Int),
+        };
+        Bounds2::intersect(&pixels, film)
+    }
+}
+
+impl Bounds2<Int> {
+    pub fn area_usize(&self) -> usize {
+        let dx = (self.p_max.x as i64 - self.p_min.x as i64).max(0) as usize;
+        let dy = (self.p_max.y as i64 - self.p_min.y as i64).max(0) as usize;
+        dx * dy
+    }
+
+    // every pixel of the half-open box, row-major; inverted bounds yield nothing
+    pub fn iter(&self) -> impl Iterator<Item = Point2<Int>> {
+        let (x0, x1) = (self.p_min.x, self.p_max.x);
+        (self.p_min.y..self.p_max.y).flat_map(move |y| (x0..x1).map(move |x| Point2::new(x, y)))
+    }
+
+    // every step-th pixel of the half-open box, always including the last
+    // row and column even when they don't land on a step boundary
+    pub fn iter_step(&self, step: Int) -> impl Iterator<Item = Point2<Int>> {
+        debug_assert!(step > 0);
+        let axis = move |lo: Int, hi: Int| {
+            let last = if hi > lo && (hi - 1 - lo) % step != 0 {
+                Some(hi - 1)
+            } else {
+                None
+            };
+            (lo..hi).step_by(step as usize).chain(last)
+        };
+        let xs = axis(self.p_min.x, self.p_max.x);
+        axis(self.p_min.y, self.p_max.y)
+            .flat_map(move |y| xs.clone().map(move |x| Point2::new(x, y)))
+    }
+}
+
+impl<T: Scalar> BoundsAxes<T> for Bounds2<T> {
+    fn axes(&self) -> Int {
+        2
+    }
+
+    fn extent(&self, axis: Int) -> T {
+        self.p_max[axis] - self.p_min[axis]
+    }
+}
+
+impl<T: Scalar> PartialEq for Bounds2<T> {
+    fn eq(&self, rhs: &Bounds2<T>) -> bool {
+        self.p_min == rhs.p_min && self.p_max == rhs.p_max
+    }
+}
+
+impl Eq for Bounds2<Int> {}
+
+impl Hash for Bounds2<Int> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.p_min.x.hash(state);
+        self.p_min.y.hash(state);
+        self.p_max.x.hash(state);
+        self.p_max.y.hash(state);
+    }
 }
 
 impl<T: Scalar> Index<Int> for Bounds2<T> {
@@ -90,9 +214,184 @@ impl<T: Scalar> Index<Int> for Bounds2<T> {
     }
 }
 
+pub type Bounds2f = Bounds2<Float>;
+pub type Bounds2i = Bounds2<Int>;
+
 #[cfg(test)]
 mod tests {
-    use crate::pbrt::{Float, Max, Min, Point2};
+    use crate::pbrt::{BoundsAxes, Float, Max, Min, Point2, Vector2f};
+
+    fn total_extent<B: BoundsAxes<Float>>(b: &B) -> Float {
+        (0..b.axes()).map(|axis| b.extent(axis)).sum()
+    }
+
+    #[test]
+    pub fn test_axes_extent() {
+        let b = super::Bounds2f::from_pts(Point2::new(1.0, -2.0), Point2::new(4.0, 3.0));
+        assert_eq!(b.axes(), 2);
+        assert_eq!(b.extent(0), 3.0);
+        assert_eq!(b.extent(1), 5.0);
+        assert_eq!(total_extent(&b), 8.0);
+    }
+
+    #[test]
+    pub fn test_intersect() {
+        let b1 = super::Bounds2f::from_pts(Point2::new(0.0, 0.0), Point2::new(2.0, 2.0));
+        let b2 = super::Bounds2f::from_pts(Point2::new(1.0, -1.0), Point2::new(3.0, 1.5));
+        let b = super::Bounds2f::intersect(&b1, &b2);
+        assert_eq!(b.p_min, Point2::new(1.0, 0.0));
+        assert_eq!(b.p_max, Point2::new(2.0, 1.5));
+    }
+
+    #[test]
+    pub fn test_expand() {
+        let b = super::Bounds2f::from_pts(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0));
+        let e = b.expand(0.5);
+        assert_eq!(e.p_min, Point2::new(-0.5, -0.5));
+        assert_eq!(e.p_max, Point2::new(1.5, 1.5));
+        assert_eq!(e.area(), 4.0);
+    }
+
+    #[test]
+    pub fn test_distance_to_point() {
+        let b = super::Bounds2f::from_pts(Point2::new(0.0, 0.0), Point2::new(2.0, 1.0));
+        assert_eq!(
+            super::Bounds2f::distance_squared(&b, &Point2::new(1.0, 0.5)),
+            0.0
+        );
+        assert_eq!(super::Bounds2f::distance(&b, &Point2::new(2.0, 1.0)), 0.0);
+        assert_eq!(super::Bounds2f::distance(&b, &Point2::new(1.0, 4.0)), 3.0);
+        assert_eq!(super::Bounds2f::distance(&b, &Point2::new(-1.5, 0.5)), 1.5);
+        assert_eq!(
+            super::Bounds2f::distance_squared(&b, &Point2::new(5.0, -4.0)),
+            25.0
+        );
+        assert_eq!(super::Bounds2f::distance(&b, &Point2::new(5.0, -4.0)), 5.0);
+        let bi = super::Bounds2i::from_pts(Point2::new(0, 0), Point2::new(2, 2));
+        assert_eq!(
+            super::Bounds2i::distance_squared(&bi, &Point2::new(-1, 4)),
+            5.0
+        );
+    }
+
+    #[test]
+    pub fn test_overlaps() {
+        let a = super::Bounds2f::from_pts(Point2::new(0.0, 0.0), Point2::new(2.0, 2.0));
+        let b = super::Bounds2f::from_pts(Point2::new(1.0, 1.0), Point2::new(3.0, 3.0));
+        let touching = super::Bounds2f::from_pts(Point2::new(2.0, 0.0), Point2::new(4.0, 2.0));
+        let apart = super::Bounds2f::from_pts(Point2::new(0.0, 3.0), Point2::new(2.0, 4.0));
+        assert!(super::Bounds2f::overlaps(&a, &b));
+        assert!(super::Bounds2f::overlaps(&a, &touching));
+        assert!(super::Bounds2f::overlaps(&touching, &a));
+        assert!(!super::Bounds2f::overlaps(&a, &apart));
+    }
+
+    #[test]
+    pub fn test_corner() {
+        let b = super::Bounds2i::from_pts(Point2::new(1, 2), Point2::new(5, 7));
+        assert_eq!(b.corner(0), Point2::new(1, 2));
+        assert_eq!(b.corner(1), Point2::new(5, 2));
+        assert_eq!(b.corner(2), Point2::new(1, 7));
+        assert_eq!(b.corner(3), Point2::new(5, 7));
+    }
+
+    #[test]
+    pub fn test_to_pixel_bounds() {
+        let film = super::Bounds2i::from_pts(Point2::new(0, 0), Point2::new(640, 480));
+        let b = super::Bounds2f::from_pts(Point2::new(10.2, 20.7), Point2::new(30.5, 40.1));
+        let pixels = b.to_pixel_bounds(&film);
+        assert_eq!(pixels.p_min, Point2::new(10, 20));
+        assert_eq!(pixels.p_max, Point2::new(31, 41));
+    }
+
+    #[test]
+    pub fn test_to_pixel_bounds_clamped() {
+        let film = super::Bounds2i::from_pts(Point2::new(0, 0), Point2::new(640, 480));
+        let b = super::Bounds2f::from_pts(Point2::new(-3.5, 470.2), Point2::new(12.1, 500.9));
+        let pixels = b.to_pixel_bounds(&film);
+        assert_eq!(pixels.p_min, Point2::new(0, 470));
+        assert_eq!(pixels.p_max, Point2::new(13, 480));
+    }
+
+    #[test]
+    pub fn test_bounds2i_hash_map_key() {
+        let mut map = std::collections::HashMap::new();
+        let tile = super::Bounds2i::from_pts(Point2::new(0, 0), Point2::new(16, 16));
+        map.insert(tile, 1);
+        map.insert(
+            super::Bounds2i::from_pts(Point2::new(16, 0), Point2::new(32, 16)),
+            2,
+        );
+        let same = super::Bounds2i::from_pts(Point2::new(16, 16), Point2::new(0, 0));
+        assert_eq!(map.get(&same), Some(&1));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    pub fn test_iter() {
+        let b = super::Bounds2i::from_pts(Point2::new(1, 4), Point2::new(4, 6));
+        let pts: Vec<Point2<super::Int>> = b.iter().collect();
+        assert_eq!(
+            pts,
+            vec![
+                Point2::new(1, 4),
+                Point2::new(2, 4),
+                Point2::new(3, 4),
+                Point2::new(1, 5),
+                Point2::new(2, 5),
+                Point2::new(3, 5),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_iter_empty() {
+        let inverted = super::Bounds2i {
+            p_min: Point2::new(3, 3),
+            p_max: Point2::new(1, 5),
+        };
+        assert_eq!(inverted.iter().count(), 0);
+        assert_eq!(super::Bounds2i::new().iter().count(), 0);
+    }
+
+    #[test]
+    pub fn test_iter_step() {
+        let b = super::Bounds2i::from_pts(Point2::new(0, 0), Point2::new(5, 5));
+        let pts: Vec<Point2<super::Int>> = b.iter_step(2).collect();
+        assert_eq!(pts.len(), 9);
+        assert_eq!(pts[0], Point2::new(0, 0));
+        assert_eq!(pts[1], Point2::new(2, 0));
+        assert_eq!(pts[8], Point2::new(4, 4));
+    }
+
+    #[test]
+    pub fn test_iter_step_includes_last() {
+        let b = super::Bounds2i::from_pts(Point2::new(1, 1), Point2::new(7, 4));
+        let pts: Vec<Point2<super::Int>> = b.iter_step(2).collect();
+        // x: 1, 3, 5, 6 and y: 1, 3
+        assert_eq!(pts.len(), 8);
+        assert_eq!(pts[3], Point2::new(6, 1));
+        assert_eq!(pts[7], Point2::new(6, 3));
+        assert_eq!(super::Bounds2i::new().iter_step(2).count(), 0);
+    }
+
+    #[test]
+    pub fn test_area_usize() {
+        let b = super::Bounds2i::from_pts(Point2::new(0, 0), Point2::new(4000, 3000));
+        assert_eq!(b.area_usize(), 12_000_000);
+    }
+
+    #[test]
+    pub fn test_area_usize_large() {
+        let b = super::Bounds2i::from_pts(Point2::new(0, 0), Point2::new(100_000, 100_000));
+        assert_eq!(b.area_usize(), 10_000_000_000);
+    }
+
+    #[test]
+    pub fn test_area_usize_empty() {
+        let b = super::Bounds2i::new();
+        assert_eq!(b.area_usize(), 0);
+    }
 
     #[test]
     pub fn test_bounds_new() {
@@ -177,6 +476,15 @@ mod tests {
         assert_eq!(l.y, 5.5);
     }
 
+    #[test]
+    pub fn test_lerp_vec() {
+        let pt1 = Point2::<Float>::new(5.0, 1.0);
+        let pt2 = Point2::<Float>::new(10.0, 10.0);
+        let b = super::Bounds2::<Float>::from_pts(pt1, pt2);
+        let l = b.lerp_vec(&Vector2f::new(0.25, 0.75));
+        assert_eq!(l, b.lerp(&Point2::<Float>::new(0.25, 0.75)));
+    }
+
     #[test]
     pub fn test_inside() {
         let min = Point2::<Float>::new(1.0, 1.0);
@@ -210,6 +518,16 @@ mod tests {
         assert_eq!(1414213.0, Float::trunc(rad * 1000000.0));
     }
 
+    #[test]
+    pub fn test_bounding_sphere_tuple() {
+        let b = super::Bounds2f::from_pts(Point2::new(0.0, 0.0), Point2::new(2.0, 2.0));
+        let mut center: Point2<Float> = Default::default();
+        let mut rad: Float = Default::default();
+        b.bounding_sphere(&mut center, &mut rad);
+        assert_eq!(b.bounding_sphere_tuple(), (center, rad));
+        assert_eq!(center, Point2::new(1.0, 1.0));
+    }
+
     #[test]
     pub fn test_offset() {
         let min = Point2::<Float>::new(1.0, 1.0);
@@ -0,0 +1,547 @@
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
+
+use crate::pbrt::{
+    gamma, BoundsAxes, Float, Int, Lerp, Point3, Point3f, Scalar, Vector3, Vector3f, PI,
+};
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Bounds3<T: Scalar> {
+    pub p_min: Point3<T>,
+    pub p_max: Point3<T>,
+}
+
+impl<T: Scalar> Bounds3<T> {
+    pub fn new() -> Self {
+        Self {
+            p_min: Point3::<T>::new(T::max_value(), T::max_value(), T::max_value()),
+            p_max: Point3::<T>::new(T::min_value(), T::min_value(), T::min_value()),
+        }
+    }
+
+    pub fn from_point(p: Point3<T>) -> Self {
+        Self { p_min: p, p_max: p }
+    }
+
+    pub fn from_pts(p1: Point3<T>, p2: Point3<T>) -> Self {
+        Self {
+            p_min: Point3::<T>::new(T::min(p1.x, p2.x), T::min(p1.y, p2.y), T::min(p1.z, p2.z)),
+            p_max: Point3::<T>::new(T::max(p1.x, p2.x), T::max(p1.y, p2.y), T::max(p1.z, p2.z)),
+        }
+    }
+
+    pub fn corner(&self, i: Int) -> Point3<T> {
+        Point3::<T>::new(
+            self[i & 1].x,
+            self[if i & 2 != 0 { 1 } else { 0 }].y,
+            self[if i & 4 != 0 { 1 } else { 0 }].z,
+        )
+    }
+
+    pub fn diagonal(&self) -> Vector3<T> {
+        self.p_max - self.p_min
+    }
+
+    pub fn surface_area(&self) -> T {
+        let d = self.clamped_diagonal();
+        let two = T::one() + T::one();
+        two * (d.x * d.y + d.x * d.z + d.y * d.z)
+    }
+
+    pub fn volume(&self) -> T {
+        let d = self.clamped_diagonal();
+        d.x * d.y * d.z
+    }
+
+    fn clamped_diagonal(&self) -> Vector3<T> {
+        let side = |lo: T, hi: T| if hi > lo { hi - lo } else { T::zero() };
+        Vector3::<T>::new(
+            side(self.p_min.x, self.p_max.x),
+            side(self.p_min.y, self.p_max.y),
+            side(self.p_min.z, self.p_max.z),
+        )
+    }
+
+    pub fn maximum_extent(&self) -> Int {
+        let d = self.diagonal();
+        if d.x > d.y && d.x > d.z {
+            0
+        } else if d.y > d.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn lerp(&self, t: &Point3f) -> Point3<T> {
+        Point3::<T>::new(
+            T::from_float(self.p_min.x.to_float().lerp(self.p_max.x.to_float(), t.x)),
+            T::from_float(self.p_min.y.to_float().lerp(self.p_max.y.to_float(), t.y)),
+            T::from_float(self.p_min.z.to_float().lerp(self.p_max.z.to_float(), t.z)),
+        )
+    }
+
+    pub fn offset(&self, p: &Point3<T>) -> Vector3<T> {
+        let mut o = *p - self.p_min;
+        if self.p_max.x > self.p_min.x {
+            o.x /= self.p_max.x - self.p_min.x;
+        }
+        if self.p_max.y > self.p_min.y {
+            o.y /= self.p_max.y - self.p_min.y;
+        }
+        if self.p_max.z > self.p_min.z {
+            o.z /= self.p_max.z - self.p_min.z;
+        }
+        o
+    }
+
+    // child i holds corner(i) of the parent: bit k of i picks the upper half
+    // along axis k; integer centers floor, so the children tile the parent
+    pub fn subdivide(&self) -> [Bounds3<T>; 8] {
+        let two = T::one() + T::one();
+        let mid = |lo: T, hi: T| lo + (hi - lo) / two;
+        let c = Point3::<T>::new(
+            mid(self.p_min.x, self.p_max.x),
+            mid(self.p_min.y, self.p_max.y),
+            mid(self.p_min.z, self.p_max.z),
+        );
+        let mut children = [*self; 8];
+        for (i, child) in children.iter_mut().enumerate() {
+            let upper = |bit: usize| i & bit != 0;
+            child.p_min = Point3::<T>::new(
+                if upper(1) { c.x } else { self.p_min.x },
+                if upper(2) { c.y } else { self.p_min.y },
+                if upper(4) { c.z } else { self.p_min.z },
+            );
+            child.p_max = Point3::<T>::new(
+                if upper(1) { self.p_max.x } else { c.x },
+                if upper(2) { self.p_max.y } else { c.y },
+                if upper(4) { self.p_max.z } else { c.z },
+            );
+        }
+        children
+    }
+}
+
+impl Bounds3<Float> {
+    // solid angle of the cone around the box's bounding sphere as seen from
+    // p, so an overestimate; the full sphere when p is inside
+    pub fn solid_angle(&self, p: &Point3f) -> Float {
+        let center = (self.p_min + self.p_max) / 2.0;
+        let radius2 = Point3f::distance_squared(&center, &self.p_max);
+        let dist2 = Point3f::distance_squared(p, &center);
+        if dist2 <= radius2 {
+            return 4.0 * PI;
+        }
+        let cos_theta_max = (1.0 - radius2 / dist2).max(0.0).sqrt();
+        2.0 * PI * (1.0 - cos_theta_max)
+    }
+
+    // pads each axis by gamma(n) times its extent, a conservative bound for
+    // values carrying n rounding errors; a flat axis gets no padding
+    pub fn expand_by_gamma(&self, n: Int) -> Bounds3f {
+        let pad = self.diagonal() * gamma(n);
+        Bounds3f {
+            p_min: self.p_min - pad,
+            p_max: self.p_max + pad,
+        }
+    }
+}
+
+impl<T: Scalar> BoundsAxes<T> for Bounds3<T> {
+    fn axes(&self) -> Int {
+        3
+    }
+
+    fn extent(&self, axis: Int) -> T {
+        self.p_max[axis] - self.p_min[axis]
+    }
+}
+
+impl<T: Scalar> PartialEq for Bounds3<T> {
+    fn eq(&self, rhs: &Bounds3<T>) -> bool {
+        self.p_min == rhs.p_min && self.p_max == rhs.p_max
+    }
+}
+
+impl Eq for Bounds3<Int> {}
+
+impl Hash for Bounds3<Int> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.p_min.hash(state);
+        self.p_max.hash(state);
+    }
+}
+
+impl<T: Scalar> Index<Int> for Bounds3<T> {
+    type Output = Point3<T>;
+
+    fn index(&self, idx: Int) -> &Self::Output {
+        debug_assert!((0..=1).contains(&idx));
+        match idx {
+            0 => &self.p_min,
+            1 => &self.p_max,
+            _ => panic!("index {} is used to access Bounds3<T> scalar", idx),
+        }
+    }
+}
+
+pub type Bounds3f = Bounds3<Float>;
+pub type Bounds3i = Bounds3<Int>;
+
+// union of the boxes around a sphere of the given radius at both ends of a
+// linear path; conservative since the path stays inside their hull
+pub fn moving_point_bounds(p_start: &Point3f, p_end: &Point3f, radius: Float) -> Bounds3f {
+    let b = Bounds3f::from_pts(*p_start, *p_end);
+    let r = Vector3f::new(radius, radius, radius);
+    Bounds3f {
+        p_min: b.p_min - r,
+        p_max: b.p_max + r,
+    }
+}
+
+// single pass over a point stream: the box grows by min/max and the centroid
+// is an incremental mean, so nothing is buffered; with no points added the
+// bounds stay empty and the centroid is the origin
+#[derive(Debug, Copy, Clone)]
+pub struct BoundsAccumulator3 {
+    bounds: Bounds3f,
+    mean: Vector3f,
+    count: usize,
+}
+
+impl BoundsAccumulator3 {
+    pub fn new() -> Self {
+        Self {
+            bounds: Bounds3f::new(),
+            mean: Vector3f::default(),
+            count: 0,
+        }
+    }
+
+    pub fn add(&mut self, p: &Point3f) {
+        let b = &mut self.bounds;
+        b.p_min = Point3f::new(b.p_min.x.min(p.x), b.p_min.y.min(p.y), b.p_min.z.min(p.z));
+        b.p_max = Point3f::new(b.p_max.x.max(p.x), b.p_max.y.max(p.y), b.p_max.z.max(p.z));
+        self.count += 1;
+        let offset = *p - Point3f::default();
+        self.mean += (offset - self.mean) / self.count as Float;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn bounds(&self) -> Bounds3f {
+        self.bounds
+    }
+
+    pub fn centroid(&self) -> Point3f {
+        Point3f::default() + self.mean
+    }
+}
+
+impl Default for BoundsAccumulator3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::{BoundsAxes, Float, Int, Max, Min, Point3, PI};
+
+    #[test]
+    pub fn test_bounds_new() {
+        let b = super::Bounds3::<Float>::new();
+        assert_eq!(
+            b.p_min,
+            Point3::new(Float::max_value(), Float::max_value(), Float::max_value())
+        );
+        assert_eq!(
+            b.p_max,
+            Point3::new(Float::min_value(), Float::min_value(), Float::min_value())
+        );
+    }
+
+    #[test]
+    pub fn test_from_point() {
+        let p = Point3::<Float>::new(1.0, 2.0, 3.0);
+        let b = super::Bounds3f::from_point(p);
+        assert_eq!(b.p_min, p);
+        assert_eq!(b.p_max, p);
+    }
+
+    #[test]
+    pub fn test_from_pts() {
+        let pt1 = Point3::<Float>::new(1.0, 11.0, 5.0);
+        let pt2 = Point3::<Float>::new(12.0, 2.0, -5.0);
+        let b = super::Bounds3f::from_pts(pt1, pt2);
+        assert_eq!(b.p_min, Point3::new(1.0, 2.0, -5.0));
+        assert_eq!(b.p_max, Point3::new(12.0, 11.0, 5.0));
+    }
+
+    #[test]
+    pub fn test_diagonal() {
+        let b =
+            super::Bounds3f::from_pts(Point3::new(1.0, 11.0, 5.0), Point3::new(12.0, 2.0, -5.0));
+        let diag = b.diagonal();
+        assert_eq!(diag.x, 11.0);
+        assert_eq!(diag.y, 9.0);
+        assert_eq!(diag.z, 10.0);
+    }
+
+    #[test]
+    pub fn test_surface_area_volume() {
+        let b = super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(b.surface_area(), 22.0);
+        assert_eq!(b.volume(), 6.0);
+        let b = super::Bounds3i::from_pts(Point3::new(0, 0, 0), Point3::new(1, 2, 3));
+        assert_eq!(b.surface_area(), 22);
+        assert_eq!(b.volume(), 6);
+    }
+
+    #[test]
+    pub fn test_surface_area_volume_degenerate() {
+        let flat =
+            super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 1.0), Point3::new(2.0, 3.0, 1.0));
+        assert_eq!(flat.volume(), 0.0);
+        assert_eq!(flat.surface_area(), 12.0);
+        let empty = super::Bounds3i::new();
+        assert_eq!(empty.volume(), 0);
+        assert_eq!(empty.surface_area(), 0);
+    }
+
+    #[test]
+    pub fn test_max_extent() {
+        let b = super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 0.0), Point3::new(3.0, 1.0, 2.0));
+        assert_eq!(b.maximum_extent(), 0);
+        let b = super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 3.0, 2.0));
+        assert_eq!(b.maximum_extent(), 1);
+        let b = super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(b.maximum_extent(), 2);
+    }
+
+    #[test]
+    pub fn test_corner() {
+        let b = super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(b.corner(0), Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(b.corner(1), Point3::new(1.0, 0.0, 0.0));
+        assert_eq!(b.corner(2), Point3::new(0.0, 2.0, 0.0));
+        assert_eq!(b.corner(4), Point3::new(0.0, 0.0, 3.0));
+        assert_eq!(b.corner(7), Point3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    pub fn test_idx() {
+        let b =
+            super::Bounds3f::from_pts(Point3::new(1.0, 11.0, 5.0), Point3::new(12.0, 2.0, -5.0));
+        assert_eq!(b[0], Point3::new(1.0, 2.0, -5.0));
+        assert_eq!(b[1], Point3::new(12.0, 11.0, 5.0));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_idx_panic() {
+        let b =
+            super::Bounds3f::from_pts(Point3::new(1.0, 11.0, 5.0), Point3::new(12.0, 2.0, -5.0));
+        let _p = b[2];
+    }
+
+    #[test]
+    pub fn test_lerp() {
+        let b = super::Bounds3f::from_pts(Point3::new(5.0, 1.0, 0.0), Point3::new(10.0, 10.0, 4.0));
+        let l = b.lerp(&Point3::new(0.5, 0.5, 0.25));
+        assert_eq!(l, Point3::new(7.5, 5.5, 1.0));
+    }
+
+    #[test]
+    pub fn test_offset() {
+        let b = super::Bounds3f::from_pts(Point3::new(1.0, 1.0, 1.0), Point3::new(3.0, 3.0, 5.0));
+        let offset = b.offset(&Point3::new(2.0, 2.0, 2.0));
+        assert_eq!(offset.x, 0.5);
+        assert_eq!(offset.y, 0.5);
+        assert_eq!(offset.z, 0.25);
+    }
+
+    fn assert_tiles<T: crate::pbrt::Scalar>(parent: &super::Bounds3<T>) {
+        let children = parent.subdivide();
+        let union = children
+            .iter()
+            .fold(super::Bounds3::<T>::new(), |u, c| super::Bounds3 {
+                p_min: Point3::new(
+                    T::min(u.p_min.x, c.p_min.x),
+                    T::min(u.p_min.y, c.p_min.y),
+                    T::min(u.p_min.z, c.p_min.z),
+                ),
+                p_max: Point3::new(
+                    T::max(u.p_max.x, c.p_max.x),
+                    T::max(u.p_max.y, c.p_max.y),
+                    T::max(u.p_max.z, c.p_max.z),
+                ),
+            });
+        assert_eq!(union, *parent);
+        let mut total = T::zero();
+        for (i, a) in children.iter().enumerate() {
+            assert_eq!(a.corner(i as Int), parent.corner(i as Int));
+            total += a.volume();
+            for b in &children[i + 1..] {
+                let overlap = super::Bounds3 {
+                    p_min: Point3::new(
+                        T::max(a.p_min.x, b.p_min.x),
+                        T::max(a.p_min.y, b.p_min.y),
+                        T::max(a.p_min.z, b.p_min.z),
+                    ),
+                    p_max: Point3::new(
+                        T::min(a.p_max.x, b.p_max.x),
+                        T::min(a.p_max.y, b.p_max.y),
+                        T::min(a.p_max.z, b.p_max.z),
+                    ),
+                };
+                assert_eq!(overlap.volume(), T::zero());
+            }
+        }
+        assert_eq!(total, parent.volume());
+    }
+
+    #[test]
+    pub fn test_subdivide() {
+        let b = super::Bounds3f::from_pts(Point3::new(-1.0, 0.0, 2.0), Point3::new(3.0, 2.0, 6.0));
+        assert_eq!(
+            b.subdivide()[0],
+            super::Bounds3f::from_pts(Point3::new(-1.0, 0.0, 2.0), Point3::new(1.0, 1.0, 4.0))
+        );
+        assert_tiles(&b);
+    }
+
+    #[test]
+    pub fn test_subdivide_int_odd_extent() {
+        let b = super::Bounds3i::from_pts(Point3::new(0, -3, 1), Point3::new(5, 0, 2));
+        assert_eq!(
+            b.subdivide()[7],
+            super::Bounds3i::from_pts(Point3::new(2, -2, 1), Point3::new(5, 0, 2))
+        );
+        assert_tiles(&b);
+    }
+
+    #[test]
+    pub fn test_solid_angle() {
+        let b = super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let far = b.solid_angle(&Point3::new(0.5, 0.5, 1000.0));
+        assert!(far > 0.0 && far < 1e-5);
+        let near = b.solid_angle(&Point3::new(0.5, 0.5, 3.0));
+        assert!(near > far);
+        assert_eq!(b.solid_angle(&Point3::new(0.5, 0.2, 0.9)), 4.0 * PI);
+    }
+
+    #[test]
+    pub fn test_expand_by_gamma() {
+        let b = super::Bounds3f::from_pts(Point3::new(0.0, 0.0, 0.0), Point3::new(4.0, 8.0, 2.0));
+        let e3 = b.expand_by_gamma(3);
+        for axis in 0..3 {
+            assert!(e3.p_min[axis] < b.p_min[axis]);
+            assert!(e3.p_max[axis] > b.p_max[axis]);
+        }
+        let e6 = b.expand_by_gamma(6);
+        let pad3 = b.p_min.y - e3.p_min.y;
+        let pad6 = b.p_min.y - e6.p_min.y;
+        assert!(pad6 > pad3);
+        assert!((pad6 / pad3 - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    pub fn test_axes_extent() {
+        let b = super::Bounds3f::from_pts(Point3::new(1.0, -2.0, 0.0), Point3::new(4.0, 3.0, 7.0));
+        assert_eq!(b.axes(), 3);
+        assert_eq!(b.extent(0), 3.0);
+        assert_eq!(b.extent(1), 5.0);
+        assert_eq!(b.extent(2), 7.0);
+    }
+
+    #[test]
+    pub fn test_bounds3i_hash_map_key() {
+        let mut map = std::collections::HashMap::new();
+        let cell = super::Bounds3i::from_pts(Point3::new(0, 0, 0), Point3::new(8, 8, 8));
+        map.insert(cell, "cell");
+        assert_eq!(
+            map.get(&super::Bounds3i::from_pts(
+                Point3::new(8, 8, 8),
+                Point3::new(0, 0, 0)
+            )),
+            Some(&"cell")
+        );
+        assert!(!map.contains_key(&super::Bounds3i::from_pts(
+            Point3::new(0, 0, 0),
+            Point3::new(8, 8, 9)
+        )));
+    }
+
+    #[test]
+    pub fn test_moving_point_bounds() {
+        let start = Point3::new(1.0, -2.0, 0.5);
+        let end = Point3::new(-3.0, 4.0, 0.5);
+        let b = super::moving_point_bounds(&start, &end, 0.25);
+        let inside = |p: Point3<Float>| {
+            (0..3).all(|axis| b.p_min[axis] <= p[axis] && p[axis] <= b.p_max[axis])
+        };
+        for p in &[start, end] {
+            for i in 0..8 {
+                let sphere_box = super::Bounds3f::from_pts(
+                    Point3::new(p.x - 0.25, p.y - 0.25, p.z - 0.25),
+                    Point3::new(p.x + 0.25, p.y + 0.25, p.z + 0.25),
+                );
+                assert!(inside(sphere_box.corner(i)));
+            }
+        }
+        assert_eq!(b.p_min, Point3::new(-3.25, -2.25, 0.25));
+        assert_eq!(b.p_max, Point3::new(1.25, 4.25, 0.75));
+    }
+
+    #[test]
+    pub fn test_accumulator_matches_batch() {
+        let points: Vec<Point3<Float>> = (0..100)
+            .map(|i| {
+                let f = i as Float;
+                Point3::new(
+                    (f * 0.37).sin() * 10.0,
+                    f * 0.5 - 20.0,
+                    (f * 1.3).cos() * 3.0,
+                )
+            })
+            .collect();
+        let mut acc = super::BoundsAccumulator3::new();
+        for p in &points {
+            acc.add(p);
+        }
+        assert_eq!(acc.len(), points.len());
+
+        let batch = points
+            .iter()
+            .fold(super::Bounds3f::new(), |b, p| super::Bounds3f {
+                p_min: Point3::new(b.p_min.x.min(p.x), b.p_min.y.min(p.y), b.p_min.z.min(p.z)),
+                p_max: Point3::new(b.p_max.x.max(p.x), b.p_max.y.max(p.y), b.p_max.z.max(p.z)),
+            });
+        assert_eq!(acc.bounds(), batch);
+
+        let c = acc.centroid();
+        let n = points.len() as Float;
+        let sx: Float = points.iter().map(|p| p.x).sum();
+        let sy: Float = points.iter().map(|p| p.y).sum();
+        let sz: Float = points.iter().map(|p| p.z).sum();
+        assert!((c.x - sx / n).abs() < 1e-4);
+        assert!((c.y - sy / n).abs() < 1e-4);
+        assert!((c.z - sz / n).abs() < 1e-4);
+    }
+
+    #[test]
+    pub fn test_accumulator_empty() {
+        let acc = super::BoundsAccumulator3::default();
+        assert!(acc.is_empty());
+        assert_eq!(acc.bounds(), super::Bounds3f::new());
+        assert_eq!(acc.centroid(), Point3::new(0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,301 @@
+use core::ops::Index;
+use core::ops::Mul;
+
+use crate::pbrt::{difference_of_products, Float};
+
+// row-major R x C matrix; the dimensions are part of the type, so a
+// mismatched product fails to compile instead of panicking
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Matrix<const R: usize, const C: usize> {
+    pub m: [[Float; C]; R],
+}
+
+impl<const R: usize, const C: usize> Matrix<R, C> {
+    pub fn new(m: [[Float; C]; R]) -> Self {
+        Self { m }
+    }
+
+    pub fn zero() -> Self {
+        Self { m: [[0.0; C]; R] }
+    }
+
+    pub fn row(&self, i: usize) -> [Float; C] {
+        self.m[i]
+    }
+
+    pub fn col(&self, j: usize) -> [Float; R] {
+        let mut c = [0.0; R];
+        for (i, v) in c.iter_mut().enumerate() {
+            *v = self.m[i][j];
+        }
+        c
+    }
+
+    pub fn transpose(&self) -> Matrix<C, R> {
+        let mut t = Matrix::<C, R>::zero();
+        for i in 0..R {
+            for j in 0..C {
+                t.m[j][i] = self.m[i][j];
+            }
+        }
+        t
+    }
+}
+
+impl<const N: usize> Matrix<N, N> {
+    pub fn identity() -> Self {
+        let mut id = Self::zero();
+        for i in 0..N {
+            id.m[i][i] = 1.0;
+        }
+        id
+    }
+}
+
+impl<const R: usize, const C: usize> Default for Matrix<R, C> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const R: usize, const C: usize, const K: usize> Mul<Matrix<C, K>> for Matrix<R, C> {
+    type Output = Matrix<R, K>;
+
+    fn mul(self, rhs: Matrix<C, K>) -> Matrix<R, K> {
+        let mut r = Matrix::<R, K>::zero();
+        for i in 0..R {
+            for j in 0..K {
+                r.m[i][j] = (0..C).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        r
+    }
+}
+
+impl<const R: usize, const C: usize> Index<(usize, usize)> for Matrix<R, C> {
+    type Output = Float;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        &self.m[i][j]
+    }
+}
+
+pub type Matrix4x4 = Matrix<4, 4>;
+
+// both determinant and inverse expand along the 2x2 minors of the top two
+// (s) and bottom two (c) rows
+impl Matrix<4, 4> {
+    fn minors(&self) -> ([Float; 6], [Float; 6]) {
+        let m = &self.m;
+        let s = [
+            difference_of_products(m[0][0], m[1][1], m[1][0], m[0][1]),
+            difference_of_products(m[0][0], m[1][2], m[1][0], m[0][2]),
+            difference_of_products(m[0][0], m[1][3], m[1][0], m[0][3]),
+            difference_of_products(m[0][1], m[1][2], m[1][1], m[0][2]),
+            difference_of_products(m[0][1], m[1][3], m[1][1], m[0][3]),
+            difference_of_products(m[0][2], m[1][3], m[1][2], m[0][3]),
+        ];
+        let c = [
+            difference_of_products(m[2][0], m[3][1], m[3][0], m[2][1]),
+            difference_of_products(m[2][0], m[3][2], m[3][0], m[2][2]),
+            difference_of_products(m[2][0], m[3][3], m[3][0], m[2][3]),
+            difference_of_products(m[2][1], m[3][2], m[3][1], m[2][2]),
+            difference_of_products(m[2][1], m[3][3], m[3][1], m[2][3]),
+            difference_of_products(m[2][2], m[3][3], m[3][2], m[2][3]),
+        ];
+        (s, c)
+    }
+
+    pub fn determinant(&self) -> Float {
+        let (s, c) = self.minors();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    // None when the matrix is singular
+    pub fn inverse(&self) -> Option<Matrix4x4> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (s, c) = self.minors();
+        let inv_det = 1.0 / det;
+        let m = &self.m;
+        let inv = [
+            [
+                m[1][1] * c[5] + m[1][3] * c[3] - m[1][2] * c[4],
+                -m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3],
+                m[3][1] * s[5] + m[3][3] * s[3] - m[3][2] * s[4],
+                -m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3],
+            ],
+            [
+                -m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1],
+                m[0][0] * c[5] + m[0][3] * c[1] - m[0][2] * c[2],
+                -m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1],
+                m[2][0] * s[5] + m[2][3] * s[1] - m[2][2] * s[2],
+            ],
+            [
+                m[1][0] * c[4] + m[1][3] * c[0] - m[1][1] * c[2],
+                -m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0],
+                m[3][0] * s[4] + m[3][3] * s[0] - m[3][1] * s[2],
+                -m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0],
+            ],
+            [
+                -m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0],
+                m[0][0] * c[3] + m[0][2] * c[0] - m[0][1] * c[1],
+                -m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0],
+                m[2][0] * s[3] + m[2][2] * s[0] - m[2][1] * s[1],
+            ],
+        ];
+        let mut r = Matrix4x4::new(inv);
+        for row in r.m.iter_mut() {
+            for v in row.iter_mut() {
+                *v *= inv_det;
+            }
+        }
+        Some(r)
+    }
+
+    // solves m x = b through LU with partial pivoting; None when singular or
+    // when a pivot is not finite
+    pub fn solve(&self, b: &[Float; 4]) -> Option<[Float; 4]> {
+        let mut lu = self.m;
+        let mut x = *b;
+        for k in 0..4 {
+            let p = (k..4)
+                .max_by(|&i, &j| lu[i][k].abs().total_cmp(&lu[j][k].abs()))
+                .unwrap();
+            if lu[p][k] == 0.0 || !lu[p][k].is_finite() {
+                return None;
+            }
+            lu.swap(k, p);
+            x.swap(k, p);
+            let (pivot, rest) = lu.split_at_mut(k + 1);
+            let (xk, x_rest) = x.split_at_mut(k + 1);
+            let pivot = &pivot[k];
+            for (row, xi) in rest.iter_mut().zip(x_rest.iter_mut()) {
+                let f = row[k] / pivot[k];
+                for (v, p) in row[k..].iter_mut().zip(&pivot[k..]) {
+                    *v -= f * p;
+                }
+                *xi -= f * xk[k];
+            }
+        }
+        for k in (0..4).rev() {
+            let s: Float = (k + 1..4).map(|j| lu[k][j] * x[j]).sum();
+            x[k] = (x[k] - s) / lu[k][k];
+        }
+        Some(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matrix, Matrix4x4};
+    use crate::pbrt::Float;
+
+    fn sample() -> Matrix4x4 {
+        Matrix4x4::new([
+            [2.0, 0.0, 1.0, 3.0],
+            [1.0, 3.0, 0.0, -1.0],
+            [0.0, 1.0, 4.0, 2.0],
+            [1.0, 0.0, 2.0, 5.0],
+        ])
+    }
+
+    fn assert_near(a: &Matrix4x4, b: &Matrix4x4) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a[(i, j)] - b[(i, j)]).abs() < 1e-5, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_identity() {
+        let id = Matrix4x4::identity();
+        assert_eq!(id[(0, 0)], 1.0);
+        assert_eq!(id[(0, 1)], 0.0);
+        assert_eq!(id * sample(), sample());
+        assert_eq!(sample() * id, sample());
+        assert_eq!(id.determinant(), 1.0);
+        assert_eq!(id.inverse(), Some(id));
+    }
+
+    #[test]
+    pub fn test_mul_2x3_3x2() {
+        let a = Matrix::<2, 3>::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let b = Matrix::<3, 2>::new([[7.0, 8.0], [9.0, 10.0], [11.0, 12.0]]);
+        let p: Matrix<2, 2> = a * b;
+        assert_eq!(p, Matrix::<2, 2>::new([[58.0, 64.0], [139.0, 154.0]]));
+    }
+
+    #[test]
+    pub fn test_transpose_row_col() {
+        let a = Matrix::<2, 3>::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let t = a.transpose();
+        assert_eq!(t, Matrix::<3, 2>::new([[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]));
+        assert_eq!(a.row(1), [4.0, 5.0, 6.0]);
+        assert_eq!(a.col(2), [3.0, 6.0]);
+    }
+
+    #[test]
+    pub fn test_determinant() {
+        let diag = Matrix4x4::new([
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 3.0, 0.0, 0.0],
+            [0.0, 0.0, 4.0, 0.0],
+            [0.0, 0.0, 0.0, 5.0],
+        ]);
+        assert_eq!(diag.determinant(), 120.0);
+        // swapping two rows flips the sign
+        let m = sample();
+        let mut swapped = m;
+        swapped.m.swap(0, 1);
+        assert!((m.determinant() + swapped.determinant()).abs() < 1e-4);
+    }
+
+    #[test]
+    pub fn test_inverse() {
+        let m = sample();
+        let inv = m.inverse().unwrap();
+        assert_near(&(m * inv), &Matrix4x4::identity());
+        assert_near(&(inv * m), &Matrix4x4::identity());
+        assert!((m.determinant() * inv.determinant() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    pub fn test_inverse_singular() {
+        let mut m = sample();
+        m.m[3] = m.m[0];
+        assert_eq!(m.determinant(), 0.0 as Float);
+        assert!(m.inverse().is_none());
+    }
+
+    #[test]
+    pub fn test_solve() {
+        let m = sample();
+        let b = [1.0, -2.0, 3.0, 0.5];
+        let x = m.solve(&b).unwrap();
+        let mx = m * Matrix::<4, 1>::new([[x[0]], [x[1]], [x[2]], [x[3]]]);
+        for (i, v) in b.iter().enumerate() {
+            assert!((mx[(i, 0)] - v).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    pub fn test_solve_singular() {
+        let mut m = sample();
+        m.m[3] = m.m[0];
+        assert!(m.solve(&[1.0, 2.0, 3.0, 4.0]).is_none());
+    }
+
+    #[test]
+    pub fn test_solve_non_finite() {
+        let mut m = Matrix4x4::identity();
+        m.m[2][1] = Float::NAN;
+        assert!(m.solve(&[1.0, 2.0, 3.0, 4.0]).is_none());
+        let mut m = Matrix4x4::identity();
+        m.m[3][0] = Float::INFINITY;
+        assert!(m.solve(&[1.0, 2.0, 3.0, 4.0]).is_none());
+    }
+}
@@ -1,5 +1,95 @@
+use crate::pbrt::{Float, Ray, Vector3f};
 
-#[derive(Debug, Default, Copy, Clone)]
-pub struct Medium {
+#[derive(Debug, Copy, Clone)]
+pub enum Medium {
+    // constant RGB absorption and scattering coefficients throughout
+    Homogeneous {
+        sigma_a: Vector3f,
+        sigma_s: Vector3f,
+    },
+}
 
-}
\ No newline at end of file
+impl Medium {
+    pub fn homogeneous(sigma_a: Vector3f, sigma_s: Vector3f) -> Self {
+        Medium::Homogeneous { sigma_a, sigma_s }
+    }
+
+    // Beer's law over the ray's parametric extent; the distance is clamped so
+    // an unbounded ray through a clear medium stays fully transmissive
+    // instead of producing 0 * inf
+    pub fn transmittance(&self, ray: &Ray) -> Vector3f {
+        match self {
+            Medium::Homogeneous { sigma_a, sigma_s } => {
+                let sigma_t = *sigma_a + *sigma_s;
+                let dist = (ray.t_max * ray.d.length()).min(Float::MAX);
+                Vector3f::new(
+                    (-sigma_t.x * dist).exp(),
+                    (-sigma_t.y * dist).exp(),
+                    (-sigma_t.z * dist).exp(),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ray::tests::test_ray;
+    use crate::pbrt::{Float, Point3f, Vector3f};
+
+    #[test]
+    pub fn test_transmittance_clear() {
+        let clear =
+            super::Medium::homogeneous(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(0.0, 0.0, 0.0));
+        assert_eq!(
+            clear.transmittance(&test_ray(
+                Point3f::default(),
+                Vector3f::new(0.0, 0.0, 2.0),
+                Some(5.0)
+            )),
+            Vector3f::new(1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            clear.transmittance(&test_ray(
+                Point3f::default(),
+                Vector3f::new(0.0, 0.0, 2.0),
+                None
+            )),
+            Vector3f::new(1.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    pub fn test_transmittance_beer() {
+        let m =
+            super::Medium::homogeneous(Vector3f::new(0.1, 0.2, 0.0), Vector3f::new(0.15, 0.0, 0.5));
+        // t_max 2 along a direction of length 2 covers distance 4
+        let tr = m.transmittance(&test_ray(
+            Point3f::default(),
+            Vector3f::new(0.0, 0.0, 2.0),
+            Some(2.0),
+        ));
+        assert!((tr.x - (-1.0 as Float).exp()).abs() < 1e-6);
+        assert!((tr.y - (-0.8 as Float).exp()).abs() < 1e-6);
+        assert!((tr.z - (-2.0 as Float).exp()).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_transmittance_monotonic() {
+        let mut last = 1.0;
+        for i in 1..10 {
+            let sigma = i as Float * 0.1;
+            let m = super::Medium::homogeneous(
+                Vector3f::new(sigma, sigma, sigma),
+                Vector3f::new(0.0, 0.0, 0.0),
+            );
+            let tr = m.transmittance(&test_ray(
+                Point3f::default(),
+                Vector3f::new(0.0, 0.0, 2.0),
+                Some(1.0),
+            ));
+            assert!(tr.x < last);
+            last = tr.x;
+        }
+    }
+}
@@ -4,6 +4,9 @@ pub use self::vector2::*;
 mod vector3;
 pub use self::vector3::*;
 
+mod normal3;
+pub use self::normal3::*;
+
 mod point2;
 pub use self::point2::*;
 
@@ -19,8 +22,23 @@ pub use self::medium::*;
 mod ray_differential;
 pub use self::ray_differential::*;
 
+mod ray_packet;
+pub use self::ray_packet::*;
+
 mod bounding_box2;
 pub use self::bounding_box2::*;
 
+mod bounding_box3;
+pub use self::bounding_box3::*;
+
+mod matrix;
+pub use self::matrix::*;
+
+mod spherical;
+pub use self::spherical::*;
+
+mod sparse_grid3;
+pub use self::sparse_grid3::*;
+
 mod utils;
 pub use self::utils::*;
\ No newline at end of file
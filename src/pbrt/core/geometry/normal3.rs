@@ -0,0 +1,391 @@
+use core::fmt::Debug;
+use core::ops::Add;
+use core::ops::AddAssign;
+use core::ops::Div;
+use core::ops::DivAssign;
+use core::ops::Index;
+use core::ops::Mul;
+use core::ops::MulAssign;
+use core::ops::Neg;
+use core::ops::Sub;
+use core::ops::SubAssign;
+
+use crate::pbrt::{Dot, Float, HasNaN, Int, Point2f, Scalar, Vector3};
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Normal3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Scalar> Normal3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        debug_assert!(!x.has_nan());
+        debug_assert!(!y.has_nan());
+        debug_assert!(!z.has_nan());
+        Self { x, y, z }
+    }
+
+    pub fn length_squared(&self) -> Float {
+        let squared = self.x * self.x + self.y * self.y + self.z * self.z;
+        squared.to_float()
+    }
+
+    pub fn length(&self) -> Float {
+        self.length_squared().sqrt()
+    }
+
+    pub fn normalize(&self) -> Normal3<T> {
+        let length = self.length();
+        if length == 0.0 {
+            return *self;
+        }
+        Self::new(
+            T::from_float(self.x.to_float() / length),
+            T::from_float(self.y.to_float() / length),
+            T::from_float(self.z.to_float() / length),
+        )
+    }
+
+    pub fn dot(&self, rhs: &Normal3<T>) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    pub fn abs_dot(&self, rhs: &Normal3<T>) -> T {
+        let d = self.dot(rhs);
+        if d < T::zero() {
+            -d
+        } else {
+            d
+        }
+    }
+
+    pub fn face_forward(&self, v: &Vector3<T>) -> Normal3<T> {
+        if self.x * v.x + self.y * v.y + self.z * v.z < T::zero() {
+            -*self
+        } else {
+            *self
+        }
+    }
+}
+
+impl Normal3<Float> {
+    // octahedral encoding of a unit normal into [-1,1]^2: project onto the
+    // octahedron |x|+|y|+|z| = 1 and fold the lower half over the diagonals
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_oct(&self) -> Point2f {
+        let n = *self / (self.x.abs() + self.y.abs() + self.z.abs());
+        if n.z >= 0.0 {
+            Point2f::new(n.x, n.y)
+        } else {
+            Point2f::new((1.0 - n.y.abs()) * sign(n.x), (1.0 - n.x.abs()) * sign(n.y))
+        }
+    }
+
+    pub fn from_oct(p: &Point2f) -> Normal3f {
+        let z = 1.0 - p.x.abs() - p.y.abs();
+        let n = if z < 0.0 {
+            Normal3f::new(
+                (1.0 - p.y.abs()) * sign(p.x),
+                (1.0 - p.x.abs()) * sign(p.y),
+                z,
+            )
+        } else {
+            Normal3f::new(p.x, p.y, z)
+        };
+        n.normalize()
+    }
+}
+
+// sign with zero treated as positive, so the fold is well defined on the axes
+fn sign(v: Float) -> Float {
+    if v < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+impl Dot for Normal3<Float> {
+    fn dot(&self, other: &Self) -> Float {
+        Normal3::dot(self, other)
+    }
+}
+
+impl<T: Scalar + From<U>, U: Scalar> From<Vector3<U>> for Normal3<T> {
+    fn from(item: Vector3<U>) -> Self {
+        Self::new(item.x.into(), item.y.into(), item.z.into())
+    }
+}
+
+impl<T: Scalar + From<U>, U: Scalar> From<Normal3<U>> for Vector3<T> {
+    fn from(item: Normal3<U>) -> Self {
+        Self::new(item.x.into(), item.y.into(), item.z.into())
+    }
+}
+
+impl<T: Scalar> HasNaN for Normal3<T> {
+    fn has_nan(&self) -> bool {
+        self.x.has_nan() || self.y.has_nan() || self.z.has_nan()
+    }
+}
+
+impl<T: Scalar> PartialEq for Normal3<T> {
+    fn eq(&self, rhs: &Normal3<T>) -> bool {
+        self.x == rhs.x && self.y == rhs.y && self.z == rhs.z
+    }
+}
+
+impl<T: Scalar> Add for Normal3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        debug_assert!(!self.has_nan());
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Scalar> AddAssign for Normal3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        debug_assert!(!rhs.has_nan());
+        *self = Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z);
+    }
+}
+
+impl<T: Scalar> Sub for Normal3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        debug_assert!(!rhs.has_nan());
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Scalar> SubAssign for Normal3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        debug_assert!(!rhs.has_nan());
+        *self = Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z);
+    }
+}
+
+impl<T: Scalar> Mul<T> for Normal3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        debug_assert!(!rhs.has_nan());
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Scalar> MulAssign<T> for Normal3<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        debug_assert!(!rhs.has_nan());
+        *self = Self::new(self.x * rhs, self.y * rhs, self.z * rhs);
+    }
+}
+
+impl<T: Scalar> Div<T> for Normal3<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        debug_assert_ne!(rhs, T::zero());
+        let inv = T::one() / rhs;
+        Self::new(self.x * inv, self.y * inv, self.z * inv)
+    }
+}
+
+impl<T: Scalar> DivAssign<T> for Normal3<T> {
+    fn div_assign(&mut self, rhs: T) {
+        debug_assert!(!rhs.has_nan());
+        *self = Self::new(self.x / rhs, self.y / rhs, self.z / rhs);
+    }
+}
+
+impl<T: Scalar> Neg for Normal3<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Scalar> Index<Int> for Normal3<T> {
+    type Output = T;
+
+    fn index(&self, idx: Int) -> &Self::Output {
+        debug_assert!((0..=2).contains(&idx));
+        match idx {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("index {} to access Normal3 Scalar", idx),
+        }
+    }
+}
+
+pub type Normal3f = Normal3<Float>;
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::{Dot, Float, HasNaN, Vector3f};
+
+    #[test]
+    pub fn test_normal3_chain() {
+        let left = super::Normal3f::new(3.0, 6.0, 9.0);
+        let right = super::Normal3f::new(3.0, 6.0, 9.0);
+        let result = ((left + right) / 3.0) * 0.5;
+        assert_eq!(result.x, 1.0);
+        assert_eq!(result.y, 2.0);
+        assert_eq!(result.z, 3.0);
+    }
+
+    #[test]
+    pub fn test_normal3_length() {
+        let n = super::Normal3f::new(2.0, 3.0, 6.0);
+        assert_eq!(n.length_squared(), 49.0);
+        assert_eq!(n.length(), 7.0);
+    }
+
+    #[test]
+    pub fn test_normal3_normalize() {
+        let n = super::Normal3f::new(0.0, 3.0, 4.0).normalize();
+        assert!((n.length() - 1.0).abs() < 1e-6);
+        let zero = super::Normal3f::default();
+        assert_eq!(zero.normalize(), zero);
+    }
+
+    #[test]
+    pub fn test_normal3_dot() {
+        let left = super::Normal3f::new(1.0, 2.0, 3.0);
+        let right = super::Normal3f::new(4.0, 5.0, 6.0);
+        assert_eq!(left.dot(&right), 32.0);
+        assert_eq!(left.abs_dot(&-right), 32.0);
+        assert_eq!(Dot::dot(&left, &right), 32.0);
+    }
+
+    #[test]
+    pub fn test_normal3_face_forward() {
+        let n = super::Normal3f::new(0.0, 0.0, 1.0);
+        let v = Vector3f::new(0.0, 0.0, -1.0);
+        assert_eq!(n.face_forward(&v), super::Normal3f::new(0.0, 0.0, -1.0));
+        assert_eq!(n.face_forward(&-v), n);
+    }
+
+    #[test]
+    pub fn test_normal3_vector3() {
+        let n = super::Normal3f::new(1.0, 2.0, 3.0);
+        let v = Vector3f::from(n);
+        assert_eq!(v, Vector3f::new(1.0, 2.0, 3.0));
+        let back = super::Normal3f::from(v);
+        assert_eq!(back, n);
+        let into: Vector3f = n.into();
+        assert_eq!(into, v);
+    }
+
+    #[test]
+    pub fn test_normal3_idx() {
+        let n = super::Normal3f::new(2.0, 4.0, 6.0);
+        assert_eq!(n[0], 2.0);
+        assert_eq!(n[1], 4.0);
+        assert_eq!(n[2], 6.0);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_normal3_idx_panic() {
+        let n = super::Normal3f::new(2.0, 4.0, 6.0);
+        let _value = n[3];
+    }
+
+    #[test]
+    pub fn test_normal3_div_assign_scalar() {
+        let mut n = super::Normal3f::new(2.0, 4.0, 8.0);
+        n /= 2.0 as Float;
+        assert_eq!(n, super::Normal3f::new(1.0, 2.0, 4.0));
+    }
+
+    #[test]
+    pub fn test_normal3_mul_assign_scalar() {
+        let mut n = super::Normal3f::new(2.0, 3.0, 4.0);
+        n *= 2.0 as Float;
+        assert_eq!(n, super::Normal3f::new(4.0, 6.0, 8.0));
+    }
+
+    #[test]
+    pub fn test_normal3_eq() {
+        let left = super::Normal3f::new(1.0, 2.0, 4.0);
+        assert!(left == super::Normal3f::new(1.0, 2.0, 4.0));
+        assert!(left != super::Normal3f::new(1.0, 2.0, 5.0));
+    }
+
+    #[test]
+    pub fn test_normal3_default() {
+        let n = super::Normal3f::default();
+        assert_eq!(n.x, 0.0);
+        assert_eq!(n.y, 0.0);
+        assert_eq!(n.z, 0.0);
+    }
+
+    #[test]
+    pub fn test_normal3_nan() {
+        let n = super::Normal3f {
+            x: 0.0,
+            y: Float::NAN,
+            z: 0.0,
+        };
+        assert!(n.has_nan());
+    }
+
+    #[test]
+    pub fn test_normal3_add_assign() {
+        let mut left = super::Normal3f::new(1.0, 2.0, 3.0);
+        left += super::Normal3f::new(3.0, 4.0, 5.0);
+        assert_eq!(left, super::Normal3f::new(4.0, 6.0, 8.0));
+    }
+
+    #[test]
+    pub fn test_normal3_sub() {
+        let left = super::Normal3f::new(3.0, 6.0, 9.0);
+        let right = super::Normal3f::new(2.0, 4.0, 8.0);
+        assert_eq!(left - right, super::Normal3f::new(1.0, 2.0, 1.0));
+        let mut left = left;
+        left -= right;
+        assert_eq!(left, super::Normal3f::new(1.0, 2.0, 1.0));
+    }
+
+    #[test]
+    pub fn test_normal3_neg() {
+        let n = -super::Normal3f::new(3.0, 6.0, 9.0);
+        assert_eq!(n, super::Normal3f::new(-3.0, -6.0, -9.0));
+    }
+
+    #[test]
+    pub fn test_oct_round_trip() {
+        let normals = [
+            super::Normal3f::new(0.0, 0.0, 1.0),
+            super::Normal3f::new(0.0, 0.0, -1.0),
+            super::Normal3f::new(1.0, 0.0, 0.0),
+            super::Normal3f::new(0.3, -0.5, 0.8),
+            super::Normal3f::new(-0.2, 0.7, -0.4),
+            super::Normal3f::new(-1.0, -1.0, -1.0),
+            super::Normal3f::new(0.5, 0.5, -0.01),
+        ];
+        for n in normals.iter().map(|n| n.normalize()) {
+            let p = n.to_oct();
+            assert!(p.x.abs() <= 1.0 && p.y.abs() <= 1.0);
+            let back = super::Normal3f::from_oct(&p);
+            assert!((back - n).length() < 1e-6, "{:?} != {:?}", back, n);
+        }
+    }
+
+    #[test]
+    pub fn test_oct_lower_hemisphere_fold() {
+        // the south pole lands on the corners of the square
+        let p = super::Normal3f::new(0.0, 0.0, -1.0).to_oct();
+        assert_eq!((p.x.abs(), p.y.abs()), (1.0, 1.0));
+        let up = super::Normal3f::new(0.6, 0.0, 0.8).to_oct();
+        let down = super::Normal3f::new(0.6, 0.0, -0.8).to_oct();
+        assert!(up.x.abs() + up.y.abs() <= 1.0);
+        assert!(down.x.abs() + down.y.abs() >= 1.0);
+    }
+}
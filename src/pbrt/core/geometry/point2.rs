@@ -1,4 +1,5 @@
 use core::fmt::Debug;
+use core::fmt::Display;
 use core::ops::Add;
 use core::ops::AddAssign;
 use core::ops::Div;
@@ -12,7 +13,9 @@ use core::ops::SubAssign;
 //todo: doesn't support template
 // use auto_ops::impl_op_ex_commutative;
 
-use crate::pbrt::{Float, HasNaN, Int, One, Scalar, Vector2};
+use crate::pbrt::{
+    difference_of_products, mix_bits, ApproxEq, Float, HasNaN, Int, Lerp, One, Scalar, Vector2,
+};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Point2<T> {
@@ -42,9 +45,38 @@ impl<T: Scalar> Point2<T> {
         (left - right).length_squared()
     }
 
+    #[deprecated(note = "use Lerp::lerp")]
     pub fn lerp(t: Float, p0: &Point2<T>, p1: &Point2<T>) -> Point2<T> {
         p0 * (Float::one() - t) + p1 * t
     }
+
+    // integer points pass through unchanged
+    pub fn floor(&self) -> Point2<T> {
+        let floor = |v: T| T::from_float(v.to_float().floor());
+        Self::new(floor(self.x), floor(self.y))
+    }
+
+    pub fn ceil(&self) -> Point2<T> {
+        let ceil = |v: T| T::from_float(v.to_float().ceil());
+        Self::new(ceil(self.x), ceil(self.y))
+    }
+
+    pub fn abs(&self) -> Point2<T> {
+        let abs = |v: T| if v < T::zero() { -v } else { v };
+        Self::new(abs(self.x), abs(self.y))
+    }
+
+    pub fn min(left: &Point2<T>, right: &Point2<T>) -> Point2<T> {
+        Self::new(T::min(left.x, right.x), T::min(left.y, right.y))
+    }
+
+    pub fn max(left: &Point2<T>, right: &Point2<T>) -> Point2<T> {
+        Self::new(T::max(left.x, right.x), T::max(left.y, right.y))
+    }
+
+    pub fn permute(&self, x: Int, y: Int) -> Point2<T> {
+        Self::new(self[x], self[y])
+    }
 }
 
 impl<T: Scalar + From<U>, U: Scalar> From<Vector2<U>> for Point2<T> {
@@ -65,6 +97,18 @@ impl<T: Scalar> HasNaN for Point2<T> {
     }
 }
 
+impl ApproxEq for Point2<Float> {
+    fn approx_eq(&self, other: &Self, eps: Float) -> bool {
+        self.x.approx_eq(&other.x, eps) && self.y.approx_eq(&other.y, eps)
+    }
+}
+
+impl Lerp for Point2<Float> {
+    fn lerp(self, other: Self, t: Float) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
 impl<T: Scalar> PartialEq for Point2<T> {
     // todo: with floats nan != nan ?
     fn eq(&self, rhs: &Point2<T>) -> bool {
@@ -206,6 +250,12 @@ impl<T: Scalar> Index<Int> for Point2<T> {
     }
 }
 
+impl<T: Scalar + Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "[{}, {}]", self.x, self.y)
+    }
+}
+
 // todo: not sure
 //impl<T: Scalar> Eq for Point2<T> {}
 
@@ -215,10 +265,182 @@ pub type Point2f = Point2<Float>;
 
 pub type Point2i = Point2<i32>;
 
+// twice the signed area of abc, positive when counter-clockwise
+pub fn orientation(a: &Point2f, b: &Point2f, c: &Point2f) -> Float {
+    difference_of_products(b.x - a.x, c.y - a.y, b.y - a.y, c.x - a.x)
+}
+
+// winding-number test, so self-overlapping and concave polygons work; the
+// polygon is implicitly closed and points on an edge or vertex count as inside
+pub fn point_in_polygon(p: &Point2f, poly: &[Point2f]) -> bool {
+    let mut winding = 0;
+    for (i, a) in poly.iter().enumerate() {
+        let b = &poly[(i + 1) % poly.len()];
+        let side = orientation(a, b, p);
+        if side == 0.0
+            && p.x >= a.x.min(b.x)
+            && p.x <= a.x.max(b.x)
+            && p.y >= a.y.min(b.y)
+            && p.y <= a.y.max(b.y)
+        {
+            return true;
+        }
+        if a.y <= p.y {
+            if b.y > p.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= p.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding != 0
+}
+
+// barycentric weights of p with respect to abc; all NaN for a degenerate
+// (zero-area) triangle
+pub fn barycentric2(p: &Point2f, a: &Point2f, b: &Point2f, c: &Point2f) -> (Float, Float, Float) {
+    let area = orientation(a, b, c);
+    if area == 0.0 {
+        return (Float::NAN, Float::NAN, Float::NAN);
+    }
+    (
+        orientation(p, b, c) / area,
+        orientation(a, p, c) / area,
+        orientation(a, b, p) / area,
+    )
+}
+
+pub fn from_barycentric2(
+    bary: (Float, Float, Float),
+    a: &Point2f,
+    b: &Point2f,
+    c: &Point2f,
+) -> Point2f {
+    Point2f::new(
+        bary.0 * a.x + bary.1 * b.x + bary.2 * c.x,
+        bary.0 * a.y + bary.1 * b.y + bary.2 * c.y,
+    )
+}
+
+// offsets a stratum center by a hash-derived amount in [-cell / 2, cell / 2),
+// deterministic in both the seed and the stratum
+pub fn jitter_point(base: &Point2f, cell: Float, seed: u32) -> Point2f {
+    let h = mix_bits(((seed as u64) << 32) ^ base.x.to_bits() as u64);
+    let h = mix_bits(h ^ base.y.to_bits() as u64);
+    let to_unit = |bits: u64| (bits >> 40) as Float / (1u64 << 24) as Float;
+    let u = Vector2::new(to_unit(h), to_unit(h << 24));
+    *base + (u - Vector2::new(0.5, 0.5)) * cell
+}
+
+// approximate coverage of the unit pixel [pixel, pixel + 1) by a circle, from
+// the signed distance of the pixel center to the circle's edge
+pub fn circle_coverage(pixel: &Point2f, center: &Point2f, radius: Float) -> Float {
+    let pixel_center = *pixel + Vector2::new(0.5, 0.5);
+    let d = radius - Point2f::distance(&pixel_center, center);
+    (d + 0.5).clamp(0.0, 1.0)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::pbrt::{Float, HasNaN, Vector2f};
 
+    #[test]
+    pub fn test_orientation() {
+        let a = super::Point2f::new(0.0, 0.0);
+        let b = super::Point2f::new(1.0, 0.0);
+        let c = super::Point2f::new(0.0, 1.0);
+        assert_eq!(super::orientation(&a, &b, &c), 1.0);
+        assert_eq!(super::orientation(&a, &c, &b), -1.0);
+    }
+
+    #[test]
+    pub fn test_barycentric2_vertices() {
+        let a = super::Point2f::new(0.0, 0.0);
+        let b = super::Point2f::new(4.0, 0.0);
+        let c = super::Point2f::new(0.0, 2.0);
+        assert_eq!(super::barycentric2(&a, &a, &b, &c), (1.0, 0.0, 0.0));
+        assert_eq!(super::barycentric2(&b, &a, &b, &c), (0.0, 1.0, 0.0));
+        assert_eq!(super::barycentric2(&c, &a, &b, &c), (0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    pub fn test_barycentric2_centroid() {
+        let a = super::Point2f::new(0.0, 0.0);
+        let b = super::Point2f::new(3.0, 0.0);
+        let c = super::Point2f::new(0.0, 3.0);
+        let centroid = super::Point2f::new(1.0, 1.0);
+        let (b0, b1, b2) = super::barycentric2(&centroid, &a, &b, &c);
+        let third = 1.0 / 3.0;
+        assert!((b0 - third).abs() < 1e-6);
+        assert!((b1 - third).abs() < 1e-6);
+        assert!((b2 - third).abs() < 1e-6);
+        let back = super::from_barycentric2((b0, b1, b2), &a, &b, &c);
+        assert!(super::Point2f::distance(&back, &centroid) < 1e-6);
+    }
+
+    #[test]
+    pub fn test_barycentric2_outside() {
+        let a = super::Point2f::new(0.0, 0.0);
+        let b = super::Point2f::new(1.0, 0.0);
+        let c = super::Point2f::new(0.0, 1.0);
+        let p = super::Point2f::new(2.0, 0.5);
+        let bary = super::barycentric2(&p, &a, &b, &c);
+        assert!(bary.0 < 0.0);
+        assert_eq!(super::from_barycentric2(bary, &a, &b, &c), p);
+    }
+
+    #[test]
+    pub fn test_barycentric2_degenerate() {
+        let a = super::Point2f::new(0.0, 0.0);
+        let b = super::Point2f::new(1.0, 1.0);
+        let c = super::Point2f::new(2.0, 2.0);
+        let (b0, b1, b2) = super::barycentric2(&b, &a, &b, &c);
+        assert!(b0.is_nan() && b1.is_nan() && b2.is_nan());
+    }
+
+    #[test]
+    pub fn test_jitter_point_deterministic() {
+        let base = super::Point2f::new(3.5, 7.5);
+        let a = super::jitter_point(&base, 1.0, 42);
+        let b = super::jitter_point(&base, 1.0, 42);
+        assert_eq!(a, b);
+        assert!(a != super::jitter_point(&base, 1.0, 43));
+    }
+
+    #[test]
+    pub fn test_jitter_point_within_cell() {
+        let cell = 0.25;
+        for seed in 0..256 {
+            let base = super::Point2f::new(seed as Float * cell + cell / 2.0, cell / 2.0);
+            let p = super::jitter_point(&base, cell, seed);
+            assert!((p.x - base.x).abs() <= cell / 2.0);
+            assert!((p.y - base.y).abs() <= cell / 2.0);
+        }
+    }
+
+    #[test]
+    pub fn test_circle_coverage_inside() {
+        let pixel = super::Point2f::new(0.0, 0.0);
+        let center = super::Point2f::new(0.5, 0.5);
+        assert_eq!(super::circle_coverage(&pixel, &center, 5.0), 1.0);
+    }
+
+    #[test]
+    pub fn test_circle_coverage_outside() {
+        let pixel = super::Point2f::new(10.0, 10.0);
+        let center = super::Point2f::new(0.5, 0.5);
+        assert_eq!(super::circle_coverage(&pixel, &center, 5.0), 0.0);
+    }
+
+    #[test]
+    pub fn test_circle_coverage_edge() {
+        let pixel = super::Point2f::new(5.0, 0.0);
+        let center = super::Point2f::new(0.5, 0.5);
+        let coverage = super::circle_coverage(&pixel, &center, 5.0);
+        assert!(coverage > 0.0 && coverage < 1.0);
+        assert_eq!(coverage, 0.5);
+    }
+
     #[test]
     #[should_panic]
     pub fn test_point2_idx_panic() {
@@ -450,4 +672,119 @@ mod tests {
         assert_eq!(neg.x, -3.0);
         assert_eq!(neg.y, -6.0);
     }
+
+    #[test]
+    pub fn test_point2_floor_ceil_abs() {
+        let p = super::Point2f::new(1.4, -1.4);
+        assert_eq!(p.floor(), super::Point2f::new(1.0, -2.0));
+        assert_eq!(p.ceil(), super::Point2f::new(2.0, -1.0));
+        assert_eq!(p.abs(), super::Point2f::new(1.4, 1.4));
+        let i = super::Point2i::new(3, -4);
+        assert_eq!(i.floor(), i);
+        assert_eq!(i.ceil(), i);
+        assert_eq!(i.abs(), super::Point2i::new(3, 4));
+    }
+
+    #[test]
+    pub fn test_point2_min_max_permute() {
+        let a = super::Point2f::new(1.0, 5.0);
+        let b = super::Point2f::new(4.0, 2.0);
+        assert_eq!(super::Point2f::min(&a, &b), super::Point2f::new(1.0, 2.0));
+        assert_eq!(super::Point2f::max(&a, &b), super::Point2f::new(4.0, 5.0));
+        let p = super::Point2i::new(1, 2);
+        assert_eq!(p.permute(1, 0), super::Point2i::new(2, 1));
+    }
+
+    #[test]
+    pub fn test_point_in_polygon_convex() {
+        let square = [
+            super::Point2f::new(0.0, 0.0),
+            super::Point2f::new(2.0, 0.0),
+            super::Point2f::new(2.0, 2.0),
+            super::Point2f::new(0.0, 2.0),
+        ];
+        assert!(super::point_in_polygon(
+            &super::Point2f::new(1.0, 1.0),
+            &square
+        ));
+        assert!(!super::point_in_polygon(
+            &super::Point2f::new(3.0, 1.0),
+            &square
+        ));
+        assert!(!super::point_in_polygon(
+            &super::Point2f::new(1.0, -0.5),
+            &square
+        ));
+        // winding direction doesn't matter
+        let mut reversed = square;
+        reversed.reverse();
+        assert!(super::point_in_polygon(
+            &super::Point2f::new(1.0, 1.0),
+            &reversed
+        ));
+    }
+
+    #[test]
+    pub fn test_point_in_polygon_concave() {
+        // a U shape open at the top between x = 1 and x = 2
+        let u = [
+            super::Point2f::new(0.0, 0.0),
+            super::Point2f::new(3.0, 0.0),
+            super::Point2f::new(3.0, 3.0),
+            super::Point2f::new(2.0, 3.0),
+            super::Point2f::new(2.0, 1.0),
+            super::Point2f::new(1.0, 1.0),
+            super::Point2f::new(1.0, 3.0),
+            super::Point2f::new(0.0, 3.0),
+        ];
+        assert!(super::point_in_polygon(&super::Point2f::new(0.5, 2.0), &u));
+        assert!(super::point_in_polygon(&super::Point2f::new(1.5, 0.5), &u));
+        assert!(!super::point_in_polygon(&super::Point2f::new(1.5, 2.0), &u));
+    }
+
+    #[test]
+    pub fn test_point_in_polygon_boundary() {
+        let tri = [
+            super::Point2f::new(0.0, 0.0),
+            super::Point2f::new(4.0, 0.0),
+            super::Point2f::new(0.0, 4.0),
+        ];
+        assert!(super::point_in_polygon(
+            &super::Point2f::new(4.0, 0.0),
+            &tri
+        ));
+        assert!(super::point_in_polygon(
+            &super::Point2f::new(2.0, 2.0),
+            &tri
+        ));
+        assert!(super::point_in_polygon(
+            &super::Point2f::new(0.0, 1.0),
+            &tri
+        ));
+        assert!(!super::point_in_polygon(
+            &super::Point2f::new(5.0, 0.0),
+            &tri
+        ));
+    }
+
+    #[test]
+    pub fn test_point2_display() {
+        assert_eq!(
+            format!("{}", super::Point2f::new(1.5, -0.25)),
+            "[1.5, -0.25]"
+        );
+    }
+
+    #[test]
+    pub fn test_point2_approx_eq() {
+        use crate::pbrt::ApproxEq;
+        let exact = super::Point2f::new(1.0, 0.1);
+        let nudged = super::Point2f::new(1.0, 0.1 + 1e-7);
+        assert!(exact != nudged);
+        assert!(exact.approx_eq(&nudged, 1e-6));
+        assert!(!exact.approx_eq(&nudged, 1e-8));
+        let a = super::Point2f::new(3.0, 4.0);
+        assert!(a.approx_eq(&super::Point2f::new(3.001, 4.0), 1e-2));
+        assert!(!a.approx_eq(&super::Point2f::new(3.0, 4.5), 1e-2));
+    }
 }
@@ -1,4 +1,5 @@
 use core::fmt::Debug;
+use core::fmt::Display;
 use core::ops::Add;
 use core::ops::AddAssign;
 use core::ops::Div;
@@ -9,8 +10,12 @@ use core::ops::MulAssign;
 use core::ops::Neg;
 use core::ops::Sub;
 use core::ops::SubAssign;
+use std::hash::{Hash, Hasher};
 
-use crate::pbrt::{Float, HasNaN, Int, One, Scalar, Vector3};
+use crate::pbrt::{
+    cross, difference_of_products, scalar_triple, ApproxEq, Float, HasNaN, Int, Lerp, Normal3f,
+    One, Scalar, Vector3,
+};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Point3<T> {
@@ -36,17 +41,63 @@ impl<T: Scalar> Point3<T> {
         }
     }
 
-    fn distance(left: &Point3<T>, right: &Point3<T>) -> Float {
+    // euclidean distance between two points
+    pub fn distance(left: &Point3<T>, right: &Point3<T>) -> Float {
         (left - right).length()
     }
 
-    fn distance_squared(left: &Point3<T>, right: &Point3<T>) -> Float {
+    pub fn distance_squared(left: &Point3<T>, right: &Point3<T>) -> Float {
         (left - right).length_squared()
     }
 
-    fn lerp(t: Float, p0: &Point3<T>, p1: &Point3<T>) -> Point3<T> {
+    #[deprecated(note = "use Lerp::lerp")]
+    pub fn lerp(t: Float, p0: &Point3<T>, p1: &Point3<T>) -> Point3<T> {
         p0 * (Float::one() - t) + p1 * t
     }
+
+    // integer points pass through unchanged
+    pub fn floor(&self) -> Point3<T> {
+        let floor = |v: T| T::from_float(v.to_float().floor());
+        Self::new(floor(self.x), floor(self.y), floor(self.z))
+    }
+
+    pub fn ceil(&self) -> Point3<T> {
+        let ceil = |v: T| T::from_float(v.to_float().ceil());
+        Self::new(ceil(self.x), ceil(self.y), ceil(self.z))
+    }
+
+    pub fn abs(&self) -> Point3<T> {
+        let abs = |v: T| if v < T::zero() { -v } else { v };
+        Self::new(abs(self.x), abs(self.y), abs(self.z))
+    }
+
+    pub fn min(left: &Point3<T>, right: &Point3<T>) -> Point3<T> {
+        Self::new(
+            T::min(left.x, right.x),
+            T::min(left.y, right.y),
+            T::min(left.z, right.z),
+        )
+    }
+
+    pub fn max(left: &Point3<T>, right: &Point3<T>) -> Point3<T> {
+        Self::new(
+            T::max(left.x, right.x),
+            T::max(left.y, right.y),
+            T::max(left.z, right.z),
+        )
+    }
+
+    pub fn permute(&self, x: Int, y: Int, z: Int) -> Point3<T> {
+        Self::new(self[x], self[y], self[z])
+    }
+}
+
+impl Point3<Float> {
+    pub fn quantize(&self, step: Float) -> Point3<Float> {
+        debug_assert!(step > 0.0);
+        let snap = |v: Float| (v / step).round() * step;
+        Self::new(snap(self.x), snap(self.y), snap(self.z))
+    }
 }
 
 impl<T: Scalar + From<U>, U: Scalar> From<Vector3<U>> for Point3<T> {
@@ -63,7 +114,21 @@ impl<T: Scalar + From<U>, U: Scalar> From<Point3<U>> for Vector3<T> {
 
 impl<T: Scalar> HasNaN for Point3<T> {
     fn has_nan(&self) -> bool {
-        self.x.has_nan() || self.y.has_nan() && self.z.has_nan()
+        self.x.has_nan() || self.y.has_nan() || self.z.has_nan()
+    }
+}
+
+impl ApproxEq for Point3<Float> {
+    fn approx_eq(&self, other: &Self, eps: Float) -> bool {
+        self.x.approx_eq(&other.x, eps)
+            && self.y.approx_eq(&other.y, eps)
+            && self.z.approx_eq(&other.z, eps)
+    }
+}
+
+impl Lerp for Point3<Float> {
+    fn lerp(self, other: Self, t: Float) -> Self {
+        self * (1.0 - t) + other * t
     }
 }
 
@@ -74,6 +139,16 @@ impl<T: Scalar> PartialEq for Point3<T> {
     }
 }
 
+impl Eq for Point3<Int> {}
+
+impl Hash for Point3<Int> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.hash(state);
+        self.y.hash(state);
+        self.z.hash(state);
+    }
+}
+
 impl<T: Scalar> Add<Vector3<T>> for Point3<T> {
     type Output = Self;
 
@@ -207,17 +282,228 @@ impl<T: Scalar> Index<Int> for Point3<T> {
     }
 }
 
+impl<T: Scalar + Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "[{}, {}, {}]", self.x, self.y, self.z)
+    }
+}
+
 // todo: not sure
 //impl<T: Scalar> Eq for Point3<T> {}
 
 // todo: reference or direct value
 
 pub type Point3f = Point3<Float>;
-pub type Point2i = Point3<i32>;
+pub type Point3i = Point3<i32>;
+
+// mirror image of p across the plane through plane_point with the given
+// (not necessarily normalized) normal
+pub fn reflect_point(p: &Point3f, plane_point: &Point3f, plane_normal: &Normal3f) -> Point3f {
+    let n = Vector3::<Float>::from(*plane_normal);
+    let d = (*p - *plane_point).dot(&n) / n.dot(&n);
+    *p - n * (2.0 * d)
+}
+
+// incremental mean of the offsets from the first point, so large coordinates
+// don't swamp the running sum; an empty slice yields the origin
+pub fn centroid(points: &[Point3f]) -> Point3f {
+    let origin = match points.first() {
+        Some(p) => *p,
+        None => return Point3f::default(),
+    };
+    let mut mean = Vector3::<Float>::default();
+    for (i, p) in points.iter().enumerate().skip(1) {
+        mean += ((*p - origin) - mean) / (i + 1) as Float;
+    }
+    origin + mean
+}
+
+// sum(w_i * p_i) / sum(w_i), accumulated as offsets from the first point like
+// centroid; weights may be negative as long as they don't cancel out
+pub fn weighted_average(points: &[Point3f], weights: &[Float]) -> Point3f {
+    debug_assert_eq!(points.len(), weights.len());
+    let origin = match points.first() {
+        Some(p) => *p,
+        None => return Point3f::default(),
+    };
+    let mut sum = Vector3::<Float>::default();
+    let mut weight_sum: Float = 0.0;
+    for (p, w) in points.iter().zip(weights) {
+        sum += (*p - origin) * *w;
+        weight_sum += *w;
+    }
+    debug_assert!(weight_sum != 0.0);
+    origin + sum / weight_sum
+}
+
+// Amanatides-Woo traversal of the cells of size cell_size that the segment
+// crosses, start cell first and end cell last. Each axis gets exactly as many
+// steps as cells it spans, so float error in the crossing times can reorder
+// steps but never overshoot the end cell
+pub fn voxel_walk(
+    start: &Point3f,
+    end: &Point3f,
+    cell_size: Float,
+) -> impl Iterator<Item = Point3i> {
+    debug_assert!(cell_size > 0.0);
+    let cell_of = |p: &Point3f| {
+        Point3i::new(
+            (p.x / cell_size).floor() as Int,
+            (p.y / cell_size).floor() as Int,
+            (p.z / cell_size).floor() as Int,
+        )
+    };
+    let mut cell = cell_of(start);
+    let last = cell_of(end);
+    let d = *end - *start;
+
+    let mut step = [0 as Int; 3];
+    let mut remaining = [0 as Int; 3];
+    let mut t_max = [Float::INFINITY; 3];
+    let mut t_delta = [Float::INFINITY; 3];
+    for axis in 0..3 {
+        remaining[axis] = (last[axis as Int] - cell[axis as Int]).abs();
+        if d[axis as Int] == 0.0 {
+            continue;
+        }
+        step[axis] = if d[axis as Int] > 0.0 { 1 } else { -1 };
+        let boundary =
+            (cell[axis as Int] + if step[axis] > 0 { 1 } else { 0 }) as Float * cell_size;
+        t_max[axis] = (boundary - start[axis as Int]) / d[axis as Int];
+        t_delta[axis] = cell_size / d[axis as Int].abs();
+    }
+
+    let mut first = true;
+    std::iter::from_fn(move || {
+        if first {
+            first = false;
+            return Some(cell);
+        }
+        let axis = (0..3).filter(|&a| remaining[a] > 0).min_by(|&a, &b| {
+            t_max[a]
+                .partial_cmp(&t_max[b])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        remaining[axis] -= 1;
+        t_max[axis] += t_delta[axis];
+        match axis {
+            0 => cell.x += step[0],
+            1 => cell.y += step[1],
+            _ => cell.z += step[2],
+        }
+        Some(cell)
+    })
+}
+
+// how the barycentrics of p0, p1, p2 change for the screen-space offsets
+// dpdx and dpdy; the offsets are projected onto the triangle's plane by
+// solving the 2x2 least-squares system on its edges, and a degenerate
+// triangle reports no change
+pub fn barycentric_derivatives(
+    p0: &Point3f,
+    p1: &Point3f,
+    p2: &Point3f,
+    dpdx: &Vector3<Float>,
+    dpdy: &Vector3<Float>,
+) -> ((Float, Float, Float), (Float, Float, Float)) {
+    let e1 = *p1 - *p0;
+    let e2 = *p2 - *p0;
+    let (a, b, c) = (e1.dot(&e1), e1.dot(&e2), e2.dot(&e2));
+    let det = difference_of_products(a, c, b, b);
+    if det == 0.0 {
+        return ((0.0, 0.0, 0.0), (0.0, 0.0, 0.0));
+    }
+    let solve = |dp: &Vector3<Float>| {
+        let (r1, r2) = (e1.dot(dp), e2.dot(dp));
+        let db1 = difference_of_products(c, r1, b, r2) / det;
+        let db2 = difference_of_products(a, r2, b, r1) / det;
+        (-db1 - db2, db1, db2)
+    };
+    (solve(dpdx), solve(dpdy))
+}
+
+// eps bounds the area of the parallelogram spanned by the two edges from a
+pub fn are_collinear(a: &Point3f, b: &Point3f, c: &Point3f, eps: Float) -> bool {
+    cross(&(*b - *a), &(*c - *a)).length() <= eps
+}
+
+// eps bounds the volume of the parallelepiped spanned by the three edges from a
+pub fn are_coplanar(a: &Point3f, b: &Point3f, c: &Point3f, d: &Point3f, eps: Float) -> bool {
+    scalar_triple(&(*b - *a), &(*c - *a), &(*d - *a)).abs() <= eps
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::pbrt::{Float, HasNaN, Vector3f};
+    use crate::pbrt::{cross, Float, HasNaN, Normal3f, Vector3f};
+
+    #[test]
+    pub fn test_reflect_point_xy_plane() {
+        let p = super::Point3f::new(1.0, 2.0, 3.0);
+        let origin = super::Point3f::new(0.0, 0.0, 0.0);
+        let n = Normal3f::new(0.0, 0.0, 2.0);
+        let r = super::reflect_point(&p, &origin, &n);
+        assert_eq!(r, super::Point3f::new(1.0, 2.0, -3.0));
+    }
+
+    #[test]
+    pub fn test_reflect_point_tilted_plane() {
+        let p = super::Point3f::new(3.0, -1.0, 2.0);
+        let q = super::Point3f::new(1.0, 1.0, 1.0);
+        let n = Normal3f::new(1.0, 2.0, -1.0);
+        let r = super::reflect_point(&p, &q, &n);
+        let n = Vector3f::from(n);
+        let mid = (p + r) / 2.0;
+        assert!((mid - q).dot(&n).abs() < 1e-5);
+        assert!(cross(&(r - p), &n).length() < 1e-5);
+    }
+
+    #[test]
+    pub fn test_centroid() {
+        let points = [
+            super::Point3f::new(0.0, 0.0, 0.0),
+            super::Point3f::new(2.0, 4.0, 6.0),
+            super::Point3f::new(4.0, 2.0, 0.0),
+        ];
+        assert_eq!(super::centroid(&points), super::Point3f::new(2.0, 2.0, 2.0));
+        assert_eq!(super::centroid(&[]), super::Point3f::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    pub fn test_centroid_large_coordinates() {
+        let base = 1.0e6;
+        let points: Vec<super::Point3f> = (0..100_000)
+            .map(|i| {
+                let o = (i % 100) as Float * 0.01;
+                super::Point3f::new(base + o, base - o, base)
+            })
+            .collect();
+        let mut reference = [0.0f64; 3];
+        for p in &points {
+            reference[0] += p.x as f64;
+            reference[1] += p.y as f64;
+            reference[2] += p.z as f64;
+        }
+        let n = points.len() as f64;
+        let c = super::centroid(&points);
+        assert!((c.x as f64 - reference[0] / n).abs() < 0.1);
+        assert!((c.y as f64 - reference[1] / n).abs() < 0.1);
+        assert!((c.z as f64 - reference[2] / n).abs() < 0.1);
+    }
+
+    #[test]
+    pub fn test_point3_quantize() {
+        let pt = super::Point3f::new(1.04, -0.96, 2.049);
+        assert_eq!(pt.quantize(0.5), super::Point3f::new(1.0, -1.0, 2.0));
+        let pt = super::Point3f::new(0.26, 0.74, -0.24);
+        assert_eq!(pt.quantize(0.5), super::Point3f::new(0.5, 0.5, 0.0));
+    }
+
+    #[test]
+    pub fn test_point3_quantize_halfway() {
+        // ties round away from zero on every axis
+        let pt = super::Point3f::new(0.25, -0.25, 0.75);
+        assert_eq!(pt.quantize(0.5), super::Point3f::new(0.5, -0.5, 1.0));
+    }
 
     #[test]
     #[should_panic]
@@ -321,8 +607,8 @@ mod tests {
 
     #[test]
     pub fn test_point3_basic() {
-        let left = super::Point2i::new(1, 2, 3);
-        let right = super::Point2i::new(3, 4, 5);
+        let left = super::Point3i::new(1, 2, 3);
+        let right = super::Point3i::new(3, 4, 5);
         let sum = left + right;
         assert_eq!(sum.x, 4);
         assert_eq!(sum.y, 6);
@@ -406,6 +692,36 @@ mod tests {
         assert!(vec3f.has_nan());
     }
 
+    #[test]
+    pub fn test_point3_nan_x() {
+        let pt = super::Point3f {
+            x: Float::NAN,
+            y: 0.0,
+            z: 0.0,
+        };
+        assert!(pt.has_nan());
+    }
+
+    #[test]
+    pub fn test_point3_nan_y() {
+        let pt = super::Point3f {
+            x: 0.0,
+            y: Float::NAN,
+            z: 0.0,
+        };
+        assert!(pt.has_nan());
+    }
+
+    #[test]
+    pub fn test_point3_nan_z() {
+        let pt = super::Point3f {
+            x: 0.0,
+            y: 0.0,
+            z: Float::NAN,
+        };
+        assert!(pt.has_nan());
+    }
+
     #[test]
     pub fn test_point3_copy() {
         let vec3f = super::Point3f::new(1.0, 2.0, 3.0);
@@ -463,4 +779,228 @@ mod tests {
         assert_eq!(neg.y, -6.0);
         assert_eq!(neg.z, -9.0);
     }
+
+    #[test]
+    pub fn test_are_collinear() {
+        let a = super::Point3f::new(0.0, 0.0, 0.0);
+        let b = super::Point3f::new(1.0, 1.0, 1.0);
+        assert!(super::are_collinear(
+            &a,
+            &b,
+            &super::Point3f::new(3.0, 3.0, 3.0),
+            0.0
+        ));
+        assert!(super::are_collinear(
+            &a,
+            &b,
+            &super::Point3f::new(2.0, 2.0, 2.0001),
+            1e-3
+        ));
+        assert!(!super::are_collinear(
+            &a,
+            &b,
+            &super::Point3f::new(2.0, 0.0, 1.0),
+            1e-3
+        ));
+    }
+
+    #[test]
+    pub fn test_are_coplanar() {
+        let a = super::Point3f::new(0.0, 0.0, 0.0);
+        let b = super::Point3f::new(1.0, 0.0, 0.0);
+        let c = super::Point3f::new(0.0, 1.0, 0.0);
+        assert!(super::are_coplanar(
+            &a,
+            &b,
+            &c,
+            &super::Point3f::new(5.0, -3.0, 0.0),
+            0.0
+        ));
+        assert!(super::are_coplanar(
+            &a,
+            &b,
+            &c,
+            &super::Point3f::new(1.0, 1.0, 0.0001),
+            1e-3
+        ));
+        assert!(!super::are_coplanar(
+            &a,
+            &b,
+            &c,
+            &super::Point3f::new(1.0, 1.0, 1.0),
+            1e-3
+        ));
+    }
+
+    #[test]
+    pub fn test_weighted_average_uniform() {
+        let points = [
+            super::Point3f::new(0.0, 0.0, 0.0),
+            super::Point3f::new(4.0, 0.0, 2.0),
+            super::Point3f::new(2.0, 6.0, -2.0),
+        ];
+        let avg = super::weighted_average(&points, &[2.0, 2.0, 2.0]);
+        assert_eq!(avg, super::centroid(&points));
+    }
+
+    #[test]
+    pub fn test_weighted_average_skewed() {
+        let points = [
+            super::Point3f::new(0.0, 0.0, 0.0),
+            super::Point3f::new(10.0, 0.0, 0.0),
+        ];
+        let avg = super::weighted_average(&points, &[1.0, 3.0]);
+        assert_eq!(avg, super::Point3f::new(7.5, 0.0, 0.0));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    pub fn test_point3_distance_lerp_public_path() {
+        use crate::pbrt::{Point3, Point3f};
+        let a = Point3f::new(0.0, 0.0, 0.0);
+        let b = Point3f::new(1.0, 2.0, 2.0);
+        assert_eq!(Point3f::distance(&a, &b), 3.0);
+        assert_eq!(Point3::distance_squared(&a, &b), 9.0);
+        assert_eq!(Point3f::lerp(0.5, &a, &b), Point3f::new(0.5, 1.0, 1.0));
+    }
+
+    #[test]
+    pub fn test_voxel_walk_diagonal() {
+        let start = super::Point3f::new(0.5, 0.5, 0.5);
+        let end = super::Point3f::new(2.5, 1.5, 0.5);
+        let cells: Vec<super::Point3i> = super::voxel_walk(&start, &end, 1.0).collect();
+        assert_eq!(
+            cells,
+            vec![
+                super::Point3i::new(0, 0, 0),
+                super::Point3i::new(1, 0, 0),
+                super::Point3i::new(1, 1, 0),
+                super::Point3i::new(2, 1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_voxel_walk_3d_diagonal() {
+        let start = super::Point3f::new(0.1, 0.2, 0.3);
+        let end = super::Point3f::new(3.9, 2.8, 1.7);
+        let cells: Vec<super::Point3i> = super::voxel_walk(&start, &end, 1.0).collect();
+        // one cell to start plus one per boundary crossed: 3 + 2 + 1
+        assert_eq!(cells.len(), 7);
+        assert_eq!(cells[0], super::Point3i::new(0, 0, 0));
+        assert_eq!(*cells.last().unwrap(), super::Point3i::new(3, 2, 1));
+        for pair in cells.windows(2) {
+            let d = pair[1] - pair[0];
+            assert_eq!(d.x.abs() + d.y.abs() + d.z.abs(), 1);
+        }
+    }
+
+    #[test]
+    pub fn test_voxel_walk_axis_aligned() {
+        let start = super::Point3f::new(0.5, 0.5, 0.5);
+        let end = super::Point3f::new(0.5, 0.5, -2.5);
+        let cells: Vec<super::Point3i> = super::voxel_walk(&start, &end, 1.0).collect();
+        assert_eq!(
+            cells,
+            vec![
+                super::Point3i::new(0, 0, 0),
+                super::Point3i::new(0, 0, -1),
+                super::Point3i::new(0, 0, -2),
+                super::Point3i::new(0, 0, -3),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_voxel_walk_single_cell() {
+        let start = super::Point3f::new(4.1, 4.2, 4.3);
+        let end = super::Point3f::new(4.4, 5.9, 4.0);
+        let cells: Vec<super::Point3i> = super::voxel_walk(&start, &end, 2.0).collect();
+        assert_eq!(cells, vec![super::Point3i::new(2, 2, 2)]);
+    }
+
+    #[test]
+    pub fn test_point3_floor_ceil_abs() {
+        let p = super::Point3f::new(1.4, -1.4, 2.9);
+        assert_eq!(p.floor(), super::Point3f::new(1.0, -2.0, 2.0));
+        assert_eq!(p.ceil(), super::Point3f::new(2.0, -1.0, 3.0));
+        assert_eq!(p.abs(), super::Point3f::new(1.4, 1.4, 2.9));
+        let i = super::Point3i::new(3, -4, 0);
+        assert_eq!(i.floor(), i);
+        assert_eq!(i.ceil(), i);
+        assert_eq!(i.abs(), super::Point3i::new(3, 4, 0));
+    }
+
+    #[test]
+    pub fn test_point3_min_max_permute() {
+        let a = super::Point3f::new(1.0, 5.0, 3.0);
+        let b = super::Point3f::new(4.0, 2.0, 6.0);
+        assert_eq!(
+            super::Point3f::min(&a, &b),
+            super::Point3f::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            super::Point3f::max(&a, &b),
+            super::Point3f::new(4.0, 5.0, 6.0)
+        );
+        let p = super::Point3i::new(1, 2, 3);
+        assert_eq!(p.permute(1, 2, 0), super::Point3i::new(2, 3, 1));
+    }
+
+    #[test]
+    pub fn test_barycentric_derivatives() {
+        let p0 = super::Point3f::new(0.0, 0.0, 0.0);
+        let p1 = super::Point3f::new(2.0, 0.0, 0.0);
+        let p2 = super::Point3f::new(0.0, 2.0, 0.0);
+        // the z part of dpdx is off the plane and must not contribute
+        let dpdx = Vector3f::new(0.2, 0.0, 1.0);
+        let dpdy = Vector3f::new(0.0, 0.4, 0.0);
+        let (dx, dy) = super::barycentric_derivatives(&p0, &p1, &p2, &dpdx, &dpdy);
+        let close = |a: (Float, Float, Float), b: (Float, Float, Float)| {
+            (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6 && (a.2 - b.2).abs() < 1e-6
+        };
+        assert!(close(dx, (-0.1, 0.1, 0.0)), "{:?}", dx);
+        assert!(close(dy, (-0.2, 0.0, 0.2)), "{:?}", dy);
+    }
+
+    #[test]
+    pub fn test_barycentric_derivatives_degenerate() {
+        let p = super::Point3f::new(1.0, 1.0, 1.0);
+        let d = Vector3f::new(1.0, 0.0, 0.0);
+        let (dx, dy) = super::barycentric_derivatives(&p, &p, &p, &d, &d);
+        assert_eq!(dx, (0.0, 0.0, 0.0));
+        assert_eq!(dy, (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    pub fn test_point3_display() {
+        assert_eq!(
+            format!("{}", super::Point3::<i32>::new(-4, 0, 7)),
+            "[-4, 0, 7]"
+        );
+    }
+
+    #[test]
+    pub fn test_point3_approx_eq() {
+        use crate::pbrt::ApproxEq;
+        let exact = super::Point3f::new(1.0, 0.1, 3.0);
+        let nudged = super::Point3f::new(1.0, 0.1 + 1e-7, 3.0);
+        assert!(exact != nudged);
+        assert!(exact.approx_eq(&nudged, 1e-6));
+        assert!(!exact.approx_eq(&nudged, 1e-8));
+        let a = super::Point3f::new(-1.0, 0.5, 8.0);
+        assert!(a.approx_eq(&super::Point3f::new(-1.0, 0.5, 8.2), 0.25));
+        assert!(!a.approx_eq(&super::Point3f::new(-1.0, 0.5, Float::INFINITY), 0.25));
+    }
+
+    #[test]
+    pub fn test_point3_trait_lerp() {
+        use crate::pbrt::Lerp;
+        let a = super::Point3f::new(0.0, 4.0, -8.0);
+        let b = super::Point3f::new(8.0, 0.0, 8.0);
+        // 0.75 * a + 0.25 * b
+        assert_eq!(a.lerp(b, 0.25), super::Point3f::new(2.0, 3.0, -4.0));
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+    }
 }
@@ -1,4 +1,6 @@
-use crate::pbrt::{Float, HasNaN, Medium, Point3f, Vector3f};
+use crate::pbrt::{
+    difference_of_products, Float, HasNaN, Lerp, Medium, Normal3f, Point3f, Vector3f,
+};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Ray<'a> {
@@ -30,35 +32,180 @@ impl<'a> Ray<'a> {
         }
     }
 
+    pub fn eval(&self, t: Float) -> Point3f {
+        self.o + self.d * t
+    }
+
     pub fn has_nan(&self) -> bool {
         self.o.has_nan() || self.d.has_nan() || Float::is_nan(self.t_max)
     }
-}
 
-impl<'a> FnOnce<(Float,)> for Ray<'a> {
-    type Output = Point3f;
+    pub fn with_jittered_time(&self, shutter_open: Float, shutter_close: Float, u: Float) -> Self {
+        Self {
+            time: shutter_open.lerp(shutter_close, u),
+            ..*self
+        }
+    }
 
-    extern "rust-call" fn call_once(self, args: (Float,)) -> Self::Output {
-        self.call(args)
+    // the far endpoint; an unbounded ray has none, so the origin stands in
+    // rather than a point at infinity
+    pub fn at_t_max(&self) -> Point3f {
+        if self.t_max.is_infinite() {
+            self.o
+        } else {
+            self.eval(self.t_max)
+        }
     }
 }
 
-impl<'a> FnMut<(Float,)> for Ray<'a> {
-    extern "rust-call" fn call_mut(&mut self, args: (Float,)) -> Self::Output {
-        self.call(args)
-    }
+// parameters of closest approach between the lines through r1 and r2, and
+// their distance; for parallel lines t1 is 0 and t2 its projection onto r2
+pub fn ray_ray_closest(r1: &Ray, r2: &Ray) -> (Float, Float, Float) {
+    let w0 = r1.o - r2.o;
+    let a = r1.d.dot(&r1.d);
+    let b = r1.d.dot(&r2.d);
+    let c = r2.d.dot(&r2.d);
+    let d = r1.d.dot(&w0);
+    let e = r2.d.dot(&w0);
+    let denom = difference_of_products(a, c, b, b);
+    let (t1, t2) = if denom <= Float::EPSILON * a * c {
+        (0.0, e / c)
+    } else {
+        (
+            difference_of_products(b, e, c, d) / denom,
+            difference_of_products(a, e, b, d) / denom,
+        )
+    };
+    (t1, t2, (r1.eval(t1) - r2.eval(t2)).length())
 }
 
-impl<'a> Fn<(Float,)> for Ray<'a> {
-    extern "rust-call" fn call(&self, args: (Float,)) -> Self::Output {
-        let (t,) = args;
-        self.o + self.d * t
+// hits the supporting plane in (0, t_max) and keeps it when it lands within
+// the annulus inner_radius <= r <= radius; rays parallel to the plane miss
+pub fn intersect_disk(
+    ray: &Ray,
+    center: &Point3f,
+    normal: &Normal3f,
+    radius: Float,
+    inner_radius: Float,
+) -> Option<Float> {
+    let normal = Vector3f::from(*normal);
+    let denom = normal.dot(&ray.d);
+    if denom == 0.0 {
+        return None;
+    }
+    let t = normal.dot(&(*center - ray.o)) / denom;
+    if t <= 0.0 || t >= ray.t_max {
+        return None;
     }
+    let dist2 = (ray.eval(t) - *center).length_squared();
+    if dist2 > radius * radius || dist2 < inner_radius * inner_radius {
+        return None;
+    }
+    Some(t)
 }
 
 #[cfg(test)]
-mod tests {
-    use crate::pbrt::{Float, Point3f, Vector3f};
+pub(crate) mod tests {
+    use crate::pbrt::{Float, Normal3f, Point3f, Vector3f};
+
+    // also used by the ray_packet and medium tests
+    pub(crate) fn test_ray(o: Point3f, d: Vector3f, t_max: Option<Float>) -> super::Ray<'static> {
+        super::Ray::new(&o, &d, t_max, Option::None, Option::None)
+    }
+
+    #[test]
+    pub fn test_ray_ray_closest_skew() {
+        let r1 = test_ray(Point3f::new(0., 0., 0.), Vector3f::new(1., 0., 0.), None);
+        let r2 = test_ray(Point3f::new(2., -3., 1.), Vector3f::new(0., 1., 0.), None);
+        let (t1, t2, dist) = super::ray_ray_closest(&r1, &r2);
+        assert_eq!(t1, 2.0);
+        assert_eq!(t2, 3.0);
+        assert_eq!(dist, 1.0);
+    }
+
+    #[test]
+    pub fn test_ray_ray_closest_intersecting() {
+        let r1 = test_ray(Point3f::new(0., 0., 0.), Vector3f::new(1., 1., 0.), None);
+        let r2 = test_ray(Point3f::new(4., 0., 0.), Vector3f::new(-1., 1., 0.), None);
+        let (t1, t2, dist) = super::ray_ray_closest(&r1, &r2);
+        assert!((t1 - 2.0).abs() < 1e-6);
+        assert!((t2 - 2.0).abs() < 1e-6);
+        assert!(dist.abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_ray_ray_closest_parallel() {
+        let r1 = test_ray(Point3f::new(0., 0., 0.), Vector3f::new(0., 0., 1.), None);
+        let r2 = test_ray(Point3f::new(3., 4., 5.), Vector3f::new(0., 0., 2.), None);
+        let (t1, t2, dist) = super::ray_ray_closest(&r1, &r2);
+        assert_eq!(t1, 0.0);
+        assert_eq!(t2, -2.5);
+        assert_eq!(dist, 5.0);
+    }
+
+    #[test]
+    pub fn test_intersect_disk_center() {
+        let ray = test_ray(Point3f::new(0., 0., 5.), Vector3f::new(0., 0., -1.), None);
+        let center = Point3f::new(0., 0., 1.);
+        let normal = Normal3f::new(0., 0., 1.);
+        assert_eq!(
+            super::intersect_disk(&ray, &center, &normal, 2.0, 0.0),
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    pub fn test_intersect_disk_annulus() {
+        let ray = test_ray(Point3f::new(1.5, 0., 5.), Vector3f::new(0., 0., -1.), None);
+        let center = Point3f::new(0., 0., 0.);
+        let normal = Normal3f::new(0., 0., 1.);
+        assert_eq!(
+            super::intersect_disk(&ray, &center, &normal, 2.0, 1.0),
+            Some(5.0)
+        );
+    }
+
+    #[test]
+    pub fn test_intersect_disk_hole() {
+        let ray = test_ray(Point3f::new(0.5, 0., 5.), Vector3f::new(0., 0., -1.), None);
+        let center = Point3f::new(0., 0., 0.);
+        let normal = Normal3f::new(0., 0., 1.);
+        assert_eq!(
+            super::intersect_disk(&ray, &center, &normal, 2.0, 1.0),
+            None
+        );
+    }
+
+    #[test]
+    pub fn test_intersect_disk_outside() {
+        let ray = test_ray(Point3f::new(3., 0., 5.), Vector3f::new(0., 0., -1.), None);
+        let center = Point3f::new(0., 0., 0.);
+        let normal = Normal3f::new(0., 0., 1.);
+        assert_eq!(
+            super::intersect_disk(&ray, &center, &normal, 2.0, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    pub fn test_intersect_disk_t_max_parallel() {
+        let center = Point3f::new(0., 0., 0.);
+        let normal = Normal3f::new(0., 0., 1.);
+        let short = test_ray(
+            Point3f::new(0., 0., 5.),
+            Vector3f::new(0., 0., -1.),
+            Some(4.0),
+        );
+        assert_eq!(
+            super::intersect_disk(&short, &center, &normal, 2.0, 0.0),
+            None
+        );
+        let parallel = test_ray(Point3f::new(0., 0., 5.), Vector3f::new(1., 0., 0.), None);
+        assert_eq!(
+            super::intersect_disk(&parallel, &center, &normal, 2.0, 0.0),
+            None
+        );
+    }
 
     #[test]
     pub fn test_from_od() {
@@ -110,6 +257,44 @@ mod tests {
         assert!(ray.has_nan())
     }
 
+    #[test]
+    pub fn test_with_jittered_time() {
+        let ray = super::Ray::new(
+            &Point3f::new(1., 2., 3.),
+            &Vector3f::new(4., 5., 6.),
+            Option::Some(10.0),
+            Option::None,
+            Option::None,
+        );
+        let open = ray.with_jittered_time(0.25, 0.75, 0.0);
+        assert_eq!(open.time, 0.25);
+        let close = ray.with_jittered_time(0.25, 0.75, 1.0);
+        assert_eq!(close.time, 0.75);
+        let mid = ray.with_jittered_time(0.25, 0.75, 0.5);
+        assert_eq!(mid.time, 0.5);
+        assert_eq!(mid.o, ray.o);
+        assert_eq!(mid.d, ray.d);
+        assert_eq!(mid.t_max, ray.t_max);
+        assert!(mid.medium.is_none());
+    }
+
+    #[test]
+    pub fn test_at_t_max() {
+        let ray = test_ray(
+            Point3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(1.0, 0.0, 0.0),
+            Some(2.0),
+        );
+        assert_eq!(ray.at_t_max(), Point3f::new(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    pub fn test_at_t_max_infinite() {
+        let o = Point3f::new(1.0, 2.0, 3.0);
+        let ray = test_ray(o, Vector3f::new(1.0, 0.0, 0.0), None);
+        assert_eq!(ray.at_t_max(), o);
+    }
+
     #[test]
     pub fn test_interpol() {
         let ray = super::Ray::new(
@@ -119,9 +304,9 @@ mod tests {
             Option::None,
             Option::None,
         );
-        let interpol = ray(0.5);
+        let interpol = ray.eval(0.5);
         assert_eq!(interpol, Point3f::new(2., 3., 4.));
-        let interpol = ray(2.0);
+        let interpol = ray.eval(2.0);
         assert_eq!(interpol, Point3f::new(5., 6., 7.));
     }
 
@@ -134,9 +319,9 @@ mod tests {
             Option::None,
             Option::None,
         );
-        let interpol = ray(0.5);
+        let interpol = ray.eval(0.5);
         assert_eq!(interpol, Point3f::new(2., 3., 4.));
-        let interpol = ray(2.0);
+        let interpol = ray.eval(2.0);
         assert_eq!(interpol, Point3f::new(5., 6., 7.));
     }
 }
@@ -1,4 +1,4 @@
-use crate::pbrt::{Float, HasNaN, Point3f, Ray, Vector3f};
+use crate::pbrt::{cross, Float, HasNaN, Point3f, Ray, Vector3f};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct RayDifferential<'a> {
@@ -58,6 +58,16 @@ impl<'a> RayDifferential<'a> {
         self.rx_direction = self.ray.d + (self.rx_direction - self.ray.d) * s;
         self.ry_direction = self.ray.d + (self.ry_direction - self.ray.d) * s;
     }
+
+    pub fn footprint_area(&self, t: Float) -> Float {
+        if !self.has_differential {
+            return 0.0;
+        }
+        let p = self.ray.o + self.ray.d * t;
+        let dx = (self.rx_origin + self.rx_direction * t) - p;
+        let dy = (self.ry_origin + self.ry_direction * t) - p;
+        cross(&dx, &dy).length()
+    }
 }
 
 #[cfg(test)]
@@ -120,6 +130,31 @@ mod tests {
         assert_eq!(ray_diff.ry_direction, ry_d);
     }
 
+    #[test]
+    pub fn test_footprint_area() {
+        let o = &Point3f::new(0., 0., 0.);
+        let d = &Vector3f::new(0., 0., 1.);
+        let ray_diff = super::RayDifferential::new(
+            o,
+            d,
+            true,
+            &Some(Point3f::new(1., 0., 0.)),
+            &Some(Point3f::new(0., 1., 0.)),
+            &Some(Vector3f::new(0.1, 0., 1.)),
+            &Some(Vector3f::new(0., 0.1, 1.)),
+        );
+        assert_eq!(ray_diff.footprint_area(0.0), 1.0);
+        assert!((ray_diff.footprint_area(10.0) - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    pub fn test_footprint_area_no_differential() {
+        let o = &Point3f::new(0., 0., 0.);
+        let d = &Vector3f::new(0., 0., 1.);
+        let ray_diff = super::RayDifferential::from_od(o, d);
+        assert_eq!(ray_diff.footprint_area(5.0), 0.0);
+    }
+
     #[test]
     pub fn test_scale_differential() {
         let o = &Point3f::new(1., 2., 3.);
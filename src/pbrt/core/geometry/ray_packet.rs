@@ -0,0 +1,124 @@
+use crate::pbrt::{Bounds3f, Float, Point3f, Ray, Vector3f};
+
+// structure-of-arrays copy of N rays so the per-ray loops over a box can be
+// vectorized; medium and time are left behind since traversal doesn't use them
+#[derive(Debug, Copy, Clone)]
+pub struct RayPacket<const N: usize> {
+    pub o: [Point3f; N],
+    pub d: [Vector3f; N],
+    pub t_max: [Float; N],
+}
+
+impl<const N: usize> RayPacket<N> {
+    pub fn from_rays(rays: &[Ray; N]) -> Self {
+        let mut packet = Self {
+            o: [Point3f::default(); N],
+            d: [Vector3f::default(); N],
+            t_max: [0.0; N],
+        };
+        for (i, ray) in rays.iter().enumerate() {
+            packet.o[i] = ray.o;
+            packet.d[i] = ray.d;
+            packet.t_max[i] = ray.t_max;
+        }
+        packet
+    }
+
+    // slab test of every ray against b over [0, t_max]; the comparisons are
+    // written so a NaN from 0 * inf on an axis-parallel ray leaves the
+    // interval unchanged instead of poisoning it
+    pub fn intersect_bounds3(&self, b: &Bounds3f) -> [bool; N] {
+        let mut hits = [false; N];
+        for (i, hit) in hits.iter_mut().enumerate() {
+            let mut t0: Float = 0.0;
+            let mut t1 = self.t_max[i];
+            for axis in 0..3 {
+                let inv_d = 1.0 / self.d[i][axis];
+                let mut t_near = (b.p_min[axis] - self.o[i][axis]) * inv_d;
+                let mut t_far = (b.p_max[axis] - self.o[i][axis]) * inv_d;
+                if t_near > t_far {
+                    std::mem::swap(&mut t_near, &mut t_far);
+                }
+                t0 = if t_near > t0 { t_near } else { t0 };
+                t1 = if t_far < t1 { t_far } else { t1 };
+            }
+            *hit = t0 <= t1;
+        }
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ray::tests::test_ray;
+    use crate::pbrt::{Bounds3f, Float, Point3f, Vector3f};
+
+    #[test]
+    pub fn test_intersect_bounds3() {
+        let b = Bounds3f::from_pts(Point3f::new(-1.0, -1.0, -1.0), Point3f::new(1.0, 1.0, 1.0));
+        let rays = [
+            // straight through
+            test_ray(
+                Point3f::new(0.0, 0.0, -5.0),
+                Vector3f::new(0.0, 0.0, 1.0),
+                None,
+            ),
+            // pointing away
+            test_ray(
+                Point3f::new(0.0, 0.0, -5.0),
+                Vector3f::new(0.0, 0.0, -1.0),
+                None,
+            ),
+            // passes beside the box
+            test_ray(
+                Point3f::new(3.0, 0.0, -5.0),
+                Vector3f::new(0.0, 0.0, 1.0),
+                None,
+            ),
+            // stops short of the box
+            test_ray(
+                Point3f::new(0.0, 0.0, -5.0),
+                Vector3f::new(0.0, 0.0, 1.0),
+                Some(2.0),
+            ),
+            // diagonal through a corner region
+            test_ray(
+                Point3f::new(-3.0, -3.0, -3.0),
+                Vector3f::new(1.0, 1.0, 1.0),
+                None,
+            ),
+            // starts inside
+            test_ray(
+                Point3f::new(0.5, 0.5, 0.5),
+                Vector3f::new(1.0, 0.0, 0.0),
+                None,
+            ),
+        ];
+        let packet = super::RayPacket::from_rays(&rays);
+        assert_eq!(
+            packet.intersect_bounds3(&b),
+            [true, false, false, false, true, true]
+        );
+    }
+
+    #[test]
+    pub fn test_from_rays() {
+        let rays = [
+            test_ray(
+                Point3f::new(1.0, 2.0, 3.0),
+                Vector3f::new(0.0, 1.0, 0.0),
+                Some(4.0),
+            ),
+            test_ray(
+                Point3f::new(-1.0, 0.0, 0.0),
+                Vector3f::new(1.0, 0.0, 0.0),
+                None,
+            ),
+        ];
+        let packet = super::RayPacket::from_rays(&rays);
+        assert_eq!(packet.o[0], Point3f::new(1.0, 2.0, 3.0));
+        assert_eq!(packet.d[1], Vector3f::new(1.0, 0.0, 0.0));
+        assert_eq!(packet.t_max[0], 4.0);
+        assert_eq!(packet.t_max[1], Float::INFINITY);
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use crate::pbrt::{Bounds3f, Float, Int, Point3f, Point3i, Vector3};
+
+// the cell itself and its 26 surrounding cells
+pub fn grid_neighbors(p: &Point3i) -> [Point3i; 27] {
+    let mut neighbors = [*p; 27];
+    let mut i = 0;
+    for dz in -1..=1 {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                neighbors[i] = *p + Vector3::new(dx, dy, dz);
+                i += 1;
+            }
+        }
+    }
+    neighbors
+}
+
+#[derive(Debug, Clone)]
+pub struct SparseGrid3<V> {
+    cells: HashMap<Point3i, V>,
+}
+
+impl<V> SparseGrid3<V> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, p: Point3i, value: V) -> Option<V> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn get(&self, p: &Point3i) -> Option<&V> {
+        self.cells.get(p)
+    }
+
+    pub fn get_mut(&mut self, p: &Point3i) -> Option<&mut V> {
+        self.cells.get_mut(p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn neighbors(&self, p: &Point3i) -> Vec<(Point3i, &V)> {
+        grid_neighbors(p)
+            .iter()
+            .filter_map(|n| self.cells.get(n).map(|v| (*n, v)))
+            .collect()
+    }
+}
+
+impl<V> Default for SparseGrid3<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// uniform bucketing of a point cloud for nearest-point queries; the cell size
+// aims for about one point per cell across the given bounds
+#[derive(Debug, Clone)]
+pub struct PointGrid3 {
+    points: Vec<Point3f>,
+    origin: Point3f,
+    cell_size: Float,
+    cells: SparseGrid3<Vec<usize>>,
+    cell_min: Point3i,
+    cell_max: Point3i,
+}
+
+impl PointGrid3 {
+    pub fn new(points: &[Point3f], bounds: &Bounds3f) -> Self {
+        let d = bounds.diagonal();
+        let extent = d.x.max(d.y).max(d.z);
+        let per_axis = (points.len() as Float).cbrt().ceil().max(1.0);
+        let cell_size = if extent > 0.0 { extent / per_axis } else { 1.0 };
+        let mut grid = Self {
+            points: points.to_vec(),
+            origin: bounds.p_min,
+            cell_size,
+            cells: SparseGrid3::new(),
+            cell_min: Point3i::new(Int::MAX, Int::MAX, Int::MAX),
+            cell_max: Point3i::new(Int::MIN, Int::MIN, Int::MIN),
+        };
+        for (i, p) in points.iter().enumerate() {
+            let c = grid.cell_of(p);
+            grid.cell_min = Point3i::min(&grid.cell_min, &c);
+            grid.cell_max = Point3i::max(&grid.cell_max, &c);
+            match grid.cells.get_mut(&c) {
+                Some(bucket) => bucket.push(i),
+                None => {
+                    grid.cells.insert(c, vec![i]);
+                }
+            }
+        }
+        grid
+    }
+
+    fn cell_of(&self, p: &Point3f) -> Point3i {
+        let o = (*p - self.origin) / self.cell_size;
+        Point3i::new(o.x.floor() as Int, o.y.floor() as Int, o.z.floor() as Int)
+    }
+
+    // index of the closest point; searches ring by ring out from the query's
+    // cell, clamped into the occupied range so far queries stay in bounds,
+    // until no farther ring can hold a closer point. a point r rings out is
+    // at least r cells away on top of the query's gap to the clamped cell
+    pub fn nearest(&self, q: &Point3f) -> Option<usize> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let o = (*q - self.origin) / self.cell_size;
+        let clamp = |v: Float, lo: Int, hi: Int| v.floor().max(lo as Float).min(hi as Float) as Int;
+        let qc = Point3i::new(
+            clamp(o.x, self.cell_min.x, self.cell_max.x),
+            clamp(o.y, self.cell_min.y, self.cell_max.y),
+            clamp(o.z, self.cell_min.z, self.cell_max.z),
+        );
+        let gap = |v: Float, c: Int| (c as Float - v).max(v - (c + 1) as Float).max(0.0);
+        let gap2 = (gap(o.x, qc.x).powi(2) + gap(o.y, qc.y).powi(2) + gap(o.z, qc.z).powi(2))
+            * self.cell_size
+            * self.cell_size;
+        let reach = |lo: Int, hi: Int, v: Int| (v - lo).max(hi - v);
+        let max_ring = reach(self.cell_min.x, self.cell_max.x, qc.x)
+            .max(reach(self.cell_min.y, self.cell_max.y, qc.y))
+            .max(reach(self.cell_min.z, self.cell_max.z, qc.z));
+
+        let mut best = None;
+        let mut best_d2 = Float::INFINITY;
+        for ring in 0..=max_ring {
+            for dz in -ring..=ring {
+                for dy in -ring..=ring {
+                    for dx in -ring..=ring {
+                        if dx.abs().max(dy.abs()).max(dz.abs()) != ring {
+                            continue;
+                        }
+                        let bucket = match self.cells.get(&(qc + Vector3::new(dx, dy, dz))) {
+                            Some(bucket) => bucket,
+                            None => continue,
+                        };
+                        for &i in bucket {
+                            let d2 = Point3f::distance_squared(q, &self.points[i]);
+                            if best.is_none() || d2 < best_d2 {
+                                best = Some(i);
+                                best_d2 = d2;
+                            }
+                        }
+                    }
+                }
+            }
+            let covered = ring as Float * self.cell_size;
+            if best.is_some() && best_d2 <= covered * covered + gap2 {
+                break;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::{Bounds3f, Float, Point3f, Point3i};
+
+    #[test]
+    pub fn test_grid_neighbors() {
+        let neighbors = super::grid_neighbors(&Point3i::new(1, 2, 3));
+        assert_eq!(neighbors[0], Point3i::new(0, 1, 2));
+        assert_eq!(neighbors[13], Point3i::new(1, 2, 3));
+        assert_eq!(neighbors[26], Point3i::new(2, 3, 4));
+    }
+
+    #[test]
+    pub fn test_insert_get() {
+        let mut grid = super::SparseGrid3::new();
+        assert!(grid.is_empty());
+        assert_eq!(grid.insert(Point3i::new(1, 2, 3), "a"), None);
+        assert_eq!(grid.insert(Point3i::new(1, 2, 3), "b"), Some("a"));
+        assert_eq!(grid.get(&Point3i::new(1, 2, 3)), Some(&"b"));
+        assert_eq!(grid.get(&Point3i::new(3, 2, 1)), None);
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    pub fn test_neighbors() {
+        let mut grid = super::SparseGrid3::new();
+        for z in -2..=2 {
+            for y in -2..=2 {
+                for x in -2..=2 {
+                    grid.insert(Point3i::new(x, y, z), x + y + z);
+                }
+            }
+        }
+        let neighbors = grid.neighbors(&Point3i::new(0, 0, 0));
+        assert_eq!(neighbors.len(), 27);
+        assert!(neighbors.iter().all(|(p, v)| p.x.abs() <= 1
+            && p.y.abs() <= 1
+            && p.z.abs() <= 1
+            && **v == p.x + p.y + p.z));
+
+        let corner = grid.neighbors(&Point3i::new(2, 2, 2));
+        assert_eq!(corner.len(), 8);
+    }
+
+    fn brute_force_nearest(points: &[Point3f], q: &Point3f) -> usize {
+        let mut best = 0;
+        for (i, p) in points.iter().enumerate() {
+            if Point3f::distance_squared(q, p) < Point3f::distance_squared(q, &points[best]) {
+                best = i;
+            }
+        }
+        best
+    }
+
+    #[test]
+    pub fn test_point_grid_nearest() {
+        let points: Vec<Point3f> = (0..50)
+            .map(|i| {
+                let f = i as Float;
+                Point3f::new(
+                    (f * 0.77).sin() * 5.0,
+                    (f * 1.31).cos() * 5.0,
+                    (f * 0.13).sin() * 2.0,
+                )
+            })
+            .collect();
+        let bounds =
+            Bounds3f::from_pts(Point3f::new(-5.0, -5.0, -2.0), Point3f::new(5.0, 5.0, 2.0));
+        let grid = super::PointGrid3::new(&points, &bounds);
+        let queries = [
+            Point3f::new(0.0, 0.0, 0.0),
+            Point3f::new(4.5, -4.5, 1.0),
+            Point3f::new(-3.0, 2.0, -1.5),
+            Point3f::new(20.0, 20.0, 20.0),
+            points[17],
+        ];
+        for q in &queries {
+            let i = grid.nearest(q).unwrap();
+            let expected = brute_force_nearest(&points, q);
+            assert_eq!(
+                Point3f::distance_squared(q, &points[i]),
+                Point3f::distance_squared(q, &points[expected])
+            );
+        }
+        assert_eq!(grid.nearest(&points[17]), Some(17));
+    }
+
+    #[test]
+    pub fn test_point_grid_far_query() {
+        let points: Vec<Point3f> = (0..64)
+            .map(|i| {
+                let f = i as Float;
+                Point3f::new((f * 0.41).sin(), (f * 0.93).cos(), (f * 0.27).sin())
+            })
+            .collect();
+        let bounds =
+            Bounds3f::from_pts(Point3f::new(-1.0, -1.0, -1.0), Point3f::new(1.0, 1.0, 1.0));
+        let grid = super::PointGrid3::new(&points, &bounds);
+        let queries = [
+            Point3f::new(-1e30, 0.0, 0.0),
+            Point3f::new(1e30, -1e30, 1e30),
+            Point3f::new(1e4, -1e4, 5e3),
+            Point3f::new(0.2, 1e4, 0.3),
+        ];
+        for q in &queries {
+            let i = grid.nearest(q).unwrap();
+            let expected = brute_force_nearest(&points, q);
+            assert_eq!(
+                Point3f::distance_squared(q, &points[i]),
+                Point3f::distance_squared(q, &points[expected])
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_point_grid_sparse() {
+        // far-apart points leave the query's neighborhood empty
+        let points = [
+            Point3f::new(0.0, 0.0, 0.0),
+            Point3f::new(100.0, 100.0, 100.0),
+        ];
+        let bounds = Bounds3f::from_pts(points[0], points[1]);
+        let grid = super::PointGrid3::new(&points, &bounds);
+        assert_eq!(grid.nearest(&Point3f::new(30.0, 30.0, 30.0)), Some(0));
+        assert_eq!(grid.nearest(&Point3f::new(70.0, 60.0, 80.0)), Some(1));
+        let empty = super::PointGrid3::new(&[], &bounds);
+        assert_eq!(empty.nearest(&Point3f::new(0.0, 0.0, 0.0)), None);
+    }
+}
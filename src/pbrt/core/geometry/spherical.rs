@@ -0,0 +1,66 @@
+use crate::pbrt::{Float, Vector3f, PI};
+
+pub fn spherical_direction(sin_theta: Float, cos_theta: Float, phi: Float) -> Vector3f {
+    Vector3f::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+}
+
+// v is expected to be normalized; z is clamped so rounding can't push acos
+// out of its domain
+pub fn spherical_theta(v: &Vector3f) -> Float {
+    v.z.clamp(-1.0, 1.0).acos()
+}
+
+// in [0, 2π); a tiny negative angle can round up to exactly 2π when
+// shifted, which wraps to 0
+pub fn spherical_phi(v: &Vector3f) -> Float {
+    let p = v.y.atan2(v.x);
+    if p >= 0.0 {
+        p
+    } else if p + 2.0 * PI >= 2.0 * PI {
+        0.0
+    } else {
+        p + 2.0 * PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::{Float, Vector3f, PI};
+
+    #[test]
+    pub fn test_spherical_round_trip() {
+        let dirs = [
+            Vector3f::new(1.0, 2.0, 3.0),
+            Vector3f::new(-1.0, 0.5, -0.2),
+            Vector3f::new(0.3, -4.0, 0.1),
+            Vector3f::new(-2.0, -2.0, 1.0),
+            Vector3f::new(0.0, 0.0, -1.0),
+        ];
+        for d in dirs.iter().map(|d| d.normalize()) {
+            let theta = super::spherical_theta(&d);
+            let phi = super::spherical_phi(&d);
+            assert!((0.0..2.0 * PI).contains(&phi));
+            let back = super::spherical_direction(theta.sin(), theta.cos(), phi);
+            assert!((back - d).length() < 1e-6, "{:?} != {:?}", back, d);
+        }
+    }
+
+    #[test]
+    pub fn test_spherical_phi_range() {
+        assert_eq!(super::spherical_phi(&Vector3f::new(1.0, 0.0, 0.0)), 0.0);
+        let phi = super::spherical_phi(&Vector3f::new(0.0, -1.0, 0.0));
+        assert!((phi - 1.5 * PI).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_spherical_phi_tiny_negative() {
+        let phi = super::spherical_phi(&Vector3f::new(1.0, -1e-9, 0.0));
+        assert!((0.0..2.0 * PI).contains(&phi));
+    }
+
+    #[test]
+    pub fn test_spherical_theta_clamped() {
+        let v = Vector3f::new(0.0, 0.0, 1.0 + Float::EPSILON);
+        assert_eq!(super::spherical_theta(&v), 0.0);
+    }
+}
@@ -1,6 +1,134 @@
-use crate::pbrt::{Float, One};
+use crate::pbrt::{Float, Int, Lerp};
 
+// half an ulp at 1, the bound on the relative error of one rounded operation
+pub const MACHINE_EPSILON: Float = Float::EPSILON * 0.5;
+
+// bound on the relative error accumulated by n rounded operations
+#[inline]
+pub fn gamma(n: Int) -> Float {
+    (n as Float * MACHINE_EPSILON) / (1.0 - n as Float * MACHINE_EPSILON)
+}
+
+#[deprecated(note = "use Lerp::lerp")]
 #[inline]
 pub fn lerp(t: Float, s: Float, e: Float) -> Float {
-    (Float::one() - t) * s + t * e
+    s.lerp(e, t)
+}
+
+// a * b - c * d with the rounding error of c * d compensated through fma
+#[inline]
+pub fn difference_of_products(a: Float, b: Float, c: Float, d: Float) -> Float {
+    let cd = c * d;
+    let err = (-c).mul_add(d, cd);
+    let dop = a.mul_add(b, -cd);
+    dop + err
+}
+
+#[inline]
+pub fn clamp<T: PartialOrd>(val: T, low: T, high: T) -> T {
+    if val < low {
+        low
+    } else if val > high {
+        high
+    } else {
+        val
+    }
+}
+
+// Hermite ramp from 0 at a to 1 at b, flat outside; a == b is a hard step
+#[inline]
+pub fn smoothstep(x: Float, a: Float, b: Float) -> Float {
+    if a == b {
+        return if x < a { 0.0 } else { 1.0 };
+    }
+    let t = clamp((x - a) / (b - a), 0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// real roots of a t^2 + b t + c in ascending order. The discriminant is
+// formed in f64 and q = -(b + sign(b) sqrt(disc)) / 2 keeps the two roots
+// from suffering cancellation; a == 0 degrades to the linear root
+pub fn quadratic(a: Float, b: Float, c: Float) -> Option<(Float, Float)> {
+    if a == 0.0 {
+        if b == 0.0 {
+            return None;
+        }
+        let t = -c / b;
+        return Some((t, t));
+    }
+    let discrim = b as f64 * b as f64 - 4.0 * a as f64 * c as f64;
+    if discrim < 0.0 {
+        return None;
+    }
+    let root_discrim = discrim.sqrt();
+    let q = if b < 0.0 {
+        -0.5 * (b as f64 - root_discrim)
+    } else {
+        -0.5 * (b as f64 + root_discrim)
+    };
+    if q == 0.0 {
+        // b and the discriminant are both zero, which forces c == 0
+        return Some((0.0, 0.0));
+    }
+    let t0 = (q / a as f64) as Float;
+    let t1 = (c as f64 / q) as Float;
+    Some(if t0 > t1 { (t1, t0) } else { (t0, t1) })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    pub fn test_gamma() {
+        let g1 = super::gamma(1);
+        assert!(g1 > 0.0 && g1 < 1e-6);
+        assert!(g1 >= super::MACHINE_EPSILON);
+        for n in 1..64 {
+            assert!(super::gamma(n + 1) > super::gamma(n));
+        }
+    }
+
+    #[test]
+    pub fn test_clamp() {
+        assert_eq!(super::clamp(5, 0, 3), 3);
+        assert_eq!(super::clamp(-2, 0, 3), 0);
+        assert_eq!(super::clamp(2, 0, 3), 2);
+        assert_eq!(super::clamp(1.5, -1.0, 1.0), 1.0);
+    }
+
+    #[test]
+    pub fn test_smoothstep() {
+        assert_eq!(super::smoothstep(1.0, 0.0, 2.0), 0.5);
+        assert_eq!(super::smoothstep(-1.0, 0.0, 2.0), 0.0);
+        assert_eq!(super::smoothstep(3.0, 0.0, 2.0), 1.0);
+        assert!(super::smoothstep(0.5, 0.0, 2.0) < 0.25);
+        assert_eq!(super::smoothstep(1.0, 1.0, 1.0), 1.0);
+        assert_eq!(super::smoothstep(0.9, 1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    pub fn test_quadratic_two_roots() {
+        assert_eq!(super::quadratic(1.0, -3.0, 2.0), Some((1.0, 2.0)));
+        assert_eq!(super::quadratic(-2.0, 0.0, 8.0), Some((-2.0, 2.0)));
+    }
+
+    #[test]
+    pub fn test_quadratic_double_root() {
+        assert_eq!(super::quadratic(1.0, -4.0, 4.0), Some((2.0, 2.0)));
+        assert_eq!(super::quadratic(3.0, 0.0, 0.0), Some((0.0, 0.0)));
+    }
+
+    #[test]
+    pub fn test_quadratic_no_solution() {
+        assert_eq!(super::quadratic(1.0, 0.0, 1.0), None);
+        assert_eq!(super::quadratic(0.0, 0.0, 1.0), None);
+    }
+
+    #[test]
+    pub fn test_quadratic_cancellation() {
+        // the small root of t^2 - 1e4 t + 1 is ~1e-4; the textbook formula
+        // loses most of its digits in f32
+        let (t0, t1) = super::quadratic(1.0, -1e4, 1.0).unwrap();
+        assert!((t0 - 1e-4).abs() < 1e-9);
+        assert!((t1 - 1e4).abs() < 1e-2);
+    }
 }
@@ -1,4 +1,5 @@
 use core::fmt::Debug;
+use core::fmt::Display;
 use core::ops::Add;
 use core::ops::AddAssign;
 use core::ops::Div;
@@ -10,7 +11,7 @@ use core::ops::Neg;
 use core::ops::Sub;
 use core::ops::SubAssign;
 
-use crate::pbrt::{Float, HasNaN, Int, Scalar};
+use crate::pbrt::{ApproxEq, Dot, Float, HasNaN, Int, Lerp, Scalar};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Vector2<T> {
@@ -33,6 +34,59 @@ impl<T: Scalar> Vector2<T> {
     pub fn length(&self) -> Float {
         self.length_squared().sqrt()
     }
+
+    pub fn normalize(&self) -> Vector2<T> {
+        let length = self.length();
+        if length == 0.0 {
+            return *self;
+        }
+        Self::new(
+            T::from_float(self.x.to_float() / length),
+            T::from_float(self.y.to_float() / length),
+        )
+    }
+
+    pub fn dot(&self, rhs: &Vector2<T>) -> T {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    pub fn abs_dot(&self, rhs: &Vector2<T>) -> T {
+        let d = self.dot(rhs);
+        if d < T::zero() {
+            -d
+        } else {
+            d
+        }
+    }
+
+    pub fn cross(&self, rhs: &Vector2<T>) -> T {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    // counter-clockwise quarter turn
+    pub fn perp(&self) -> Vector2<T> {
+        Self::new(-self.y, self.x)
+    }
+
+    pub fn perp_cw(&self) -> Vector2<T> {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl Vector2<Float> {
+    // complex multiplication by cos_sin = (cos θ, sin θ)
+    pub fn rotate_by_unit(&self, cos_sin: &Vector2<Float>) -> Vector2<Float> {
+        Self::new(
+            self.x * cos_sin.x - self.y * cos_sin.y,
+            self.x * cos_sin.y + self.y * cos_sin.x,
+        )
+    }
+}
+
+impl Dot for Vector2<Float> {
+    fn dot(&self, other: &Self) -> Float {
+        Vector2::dot(self, other)
+    }
 }
 
 impl<T: Scalar> HasNaN for Vector2<T> {
@@ -41,6 +95,18 @@ impl<T: Scalar> HasNaN for Vector2<T> {
     }
 }
 
+impl ApproxEq for Vector2<Float> {
+    fn approx_eq(&self, other: &Self, eps: Float) -> bool {
+        self.x.approx_eq(&other.x, eps) && self.y.approx_eq(&other.y, eps)
+    }
+}
+
+impl Lerp for Vector2<Float> {
+    fn lerp(self, other: Self, t: Float) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
 impl<T: Scalar> PartialEq for Vector2<T> {
     // todo: with floats nan != nan ?
     fn eq(&self, rhs: &Vector2<T>) -> bool {
@@ -137,12 +203,77 @@ impl<T: Scalar> Index<Int> for Vector2<T> {
     }
 }
 
+impl<T: Scalar + Display> Display for Vector2<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "[{}, {}]", self.x, self.y)
+    }
+}
+
 pub type Vector2f = Vector2<Float>;
 pub type Vector2i = Vector2<i32>;
 
 #[cfg(test)]
 mod tests {
-    use crate::pbrt::{Float, HasNaN};
+    use crate::pbrt::{Dot, Float, HasNaN};
+
+    #[test]
+    pub fn test_vector2_dot() {
+        let left = super::Vector2f::new(1.0, 2.0);
+        let right = super::Vector2f::new(3.0, 4.0);
+        assert_eq!(left.dot(&right), 11.0);
+        assert_eq!(left.abs_dot(&-right), 11.0);
+        assert_eq!(
+            super::Vector2i::new(1, 2).dot(&super::Vector2i::new(3, 4)),
+            11
+        );
+    }
+
+    #[test]
+    pub fn test_vector2_cross() {
+        let x = super::Vector2f::new(1.0, 0.0);
+        let y = super::Vector2f::new(0.0, 1.0);
+        assert_eq!(x.cross(&y), 1.0);
+        assert_eq!(y.cross(&x), -1.0);
+    }
+
+    #[test]
+    pub fn test_vector2_normalize() {
+        let v = super::Vector2f::new(3.0, 4.0).normalize();
+        assert!((v.length() - 1.0).abs() < 1e-6);
+        let zero = super::Vector2f::new(0.0, 0.0);
+        assert_eq!(zero.normalize(), zero);
+    }
+
+    #[test]
+    pub fn test_vector2_dot_trait() {
+        let left = super::Vector2f::new(1.0, 2.0);
+        let right = super::Vector2f::new(3.0, 4.0);
+        assert_eq!(Dot::dot(&left, &right), 11.0);
+    }
+
+    #[test]
+    pub fn test_vector2_rotate_by_unit() {
+        let v = super::Vector2f::new(1.0, 0.0);
+        let quarter = super::Vector2f::new(0.0, 1.0);
+        assert_eq!(v.rotate_by_unit(&quarter), super::Vector2f::new(0.0, 1.0));
+    }
+
+    #[test]
+    pub fn test_vector2_rotate_by_unit_compose() {
+        let theta: Float = 0.3;
+        let phi: Float = 1.1;
+        let r1 = super::Vector2f::new(theta.cos(), theta.sin());
+        let r2 = super::Vector2f::new(phi.cos(), phi.sin());
+        let v = super::Vector2f::new(2.0, -1.0);
+        let composed = v.rotate_by_unit(&r1).rotate_by_unit(&r2);
+        let direct = v.rotate_by_unit(&r1.rotate_by_unit(&r2));
+        let expected = v.rotate_by_unit(&super::Vector2f::new(
+            (theta + phi).cos(),
+            (theta + phi).sin(),
+        ));
+        assert!((composed - expected).length() < 1e-5);
+        assert!((direct - expected).length() < 1e-5);
+    }
 
     #[test]
     pub fn tst_vector2_chain() {
@@ -289,4 +420,33 @@ mod tests {
         assert_eq!(neg.x, -3.0);
         assert_eq!(neg.y, -6.0);
     }
+
+    #[test]
+    pub fn test_perp() {
+        let v = super::Vector2f::new(1.0, 0.0);
+        assert_eq!(v.perp(), super::Vector2f::new(0.0, 1.0));
+        assert_eq!(v.perp_cw(), super::Vector2f::new(0.0, -1.0));
+        let w = super::Vector2f::new(3.0, -2.0);
+        assert_eq!(w.dot(&w.perp()), 0.0);
+        assert_eq!(w.dot(&w.perp_cw()), 0.0);
+        assert_eq!(w.perp().perp_cw(), w);
+    }
+
+    #[test]
+    pub fn test_vector2_display() {
+        assert_eq!(format!("{}", super::Vector2::<i32>::new(1, -2)), "[1, -2]");
+    }
+
+    #[test]
+    pub fn test_vector2_approx_eq() {
+        use crate::pbrt::ApproxEq;
+        let exact = super::Vector2f::new(1.0, 0.1);
+        let nudged = super::Vector2f::new(1.0, 0.1 + 1e-7);
+        assert!(exact != nudged);
+        assert!(exact.approx_eq(&nudged, 1e-6));
+        assert!(!exact.approx_eq(&nudged, 1e-8));
+        let a = super::Vector2f::new(0.5, -2.0);
+        assert!(a.approx_eq(&super::Vector2f::new(0.5, -2.01), 0.1));
+        assert!(!a.approx_eq(&super::Vector2f::new(0.75, -2.0), 0.1));
+    }
 }
@@ -1,4 +1,5 @@
 use core::fmt::Debug;
+use core::fmt::Display;
 use core::ops::Add;
 use core::ops::AddAssign;
 use core::ops::Div;
@@ -10,7 +11,10 @@ use core::ops::Neg;
 use core::ops::Sub;
 use core::ops::SubAssign;
 
-use crate::pbrt::{Float, HasNaN, Int, Scalar};
+use crate::pbrt::{
+    difference_of_products, hash_float, mix_bits, ApproxEq, Dot, Float, HasNaN, Int, Lerp,
+    Normal3f, Point2f, Point3f, Scalar,
+};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Vector3<T> {
@@ -35,6 +39,192 @@ impl<T: Scalar> Vector3<T> {
     pub fn length(&self) -> Float {
         self.length_squared().sqrt()
     }
+
+    pub fn normalize(&self) -> Vector3<T> {
+        let length = self.length();
+        if length == 0.0 {
+            return *self;
+        }
+        Self::new(
+            T::from_float(self.x.to_float() / length),
+            T::from_float(self.y.to_float() / length),
+            T::from_float(self.z.to_float() / length),
+        )
+    }
+
+    pub fn dot(&self, rhs: &Vector3<T>) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    pub fn abs_dot(&self, rhs: &Vector3<T>) -> T {
+        let d = self.dot(rhs);
+        if d < T::zero() {
+            -d
+        } else {
+            d
+        }
+    }
+
+    pub fn min_component(&self) -> T {
+        T::min(self.x, T::min(self.y, self.z))
+    }
+
+    pub fn max_component(&self) -> T {
+        T::max(self.x, T::max(self.y, self.z))
+    }
+
+    pub fn min_dimension(&self) -> Int {
+        if self.x <= self.y && self.x <= self.z {
+            0
+        } else if self.y <= self.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn max_dimension(&self) -> Int {
+        if self.x >= self.y && self.x >= self.z {
+            0
+        } else if self.y >= self.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn permute(&self, x: Int, y: Int, z: Int) -> Vector3<T> {
+        Self::new(self[x], self[y], self[z])
+    }
+
+    pub fn abs(&self) -> Vector3<T> {
+        let abs = |v: T| if v < T::zero() { -v } else { v };
+        Self::new(abs(self.x), abs(self.y), abs(self.z))
+    }
+
+    pub fn min(left: &Vector3<T>, right: &Vector3<T>) -> Vector3<T> {
+        Self::new(
+            T::min(left.x, right.x),
+            T::min(left.y, right.y),
+            T::min(left.z, right.z),
+        )
+    }
+
+    pub fn max(left: &Vector3<T>, right: &Vector3<T>) -> Vector3<T> {
+        Self::new(
+            T::max(left.x, right.x),
+            T::max(left.y, right.y),
+            T::max(left.z, right.z),
+        )
+    }
+}
+
+impl Vector3<Float> {
+    pub fn eq_ignoring_axis(&self, other: &Vector3<Float>, axis: Int, eps: Float) -> bool {
+        debug_assert!((0..=2).contains(&axis));
+        let (a, b) = match axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            2 => (0, 1),
+            _ => panic!("axis {} to ignore in Vector3 comparison", axis),
+        };
+        (self[a] - other[a]).abs() <= eps && (self[b] - other[b]).abs() <= eps
+    }
+
+    pub fn bucket(&self, n_bins: Int, range: (Float, Float)) -> [Int; 3] {
+        debug_assert!(n_bins > 0);
+        debug_assert!(range.1 > range.0);
+        let (lo, hi) = range;
+        let bin = |v: Float| {
+            let b = ((v - lo) / (hi - lo) * n_bins as Float).floor();
+            (b.max(0.0) as Int).min(n_bins - 1)
+        };
+        [bin(self.x), bin(self.y), bin(self.z)]
+    }
+
+    pub fn scrub_nan(&self, replacement: Float) -> Vector3<Float> {
+        let scrub = |v: Float| if v.is_finite() { v } else { replacement };
+        Self::new(scrub(self.x), scrub(self.y), scrub(self.z))
+    }
+
+    pub fn quantize(&self, step: Float) -> Vector3<Float> {
+        debug_assert!(step > 0.0);
+        let snap = |v: Float| (v / step).round() * step;
+        Self::new(snap(self.x), snap(self.y), snap(self.z))
+    }
+
+    pub fn hash_key(&self) -> u64 {
+        let h = mix_bits(hash_float(self.x) ^ hash_float(self.y).rotate_left(21));
+        mix_bits(h ^ hash_float(self.z).rotate_left(42))
+    }
+
+    // shared-exponent Radiance encoding; negative components clamp to zero and
+    // values beyond the largest exponent saturate
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_rgbe(&self) -> [u8; 4] {
+        let v = self.x.max(self.y).max(self.z);
+        if v < 1e-32 {
+            return [0, 0, 0, 0];
+        }
+        let mut e = v.log2().floor() as i32 + 1;
+        if v / (2.0 as Float).powi(e) >= 1.0 {
+            e += 1;
+        }
+        if e > 127 {
+            return [255, 255, 255, 255];
+        }
+        let scale = 256.0 / (2.0 as Float).powi(e);
+        let pack = |c: Float| (c * scale).clamp(0.0, 255.0) as u8;
+        [pack(self.x), pack(self.y), pack(self.z), (e + 128) as u8]
+    }
+
+    pub fn from_rgbe(rgbe: &[u8; 4]) -> Vector3<Float> {
+        if rgbe[3] == 0 {
+            return Self::default();
+        }
+        let f = (2.0 as Float).powi(rgbe[3] as i32 - (128 + 8));
+        Self::new(
+            (rgbe[0] as Float + 0.5) * f,
+            (rgbe[1] as Float + 0.5) * f,
+            (rgbe[2] as Float + 0.5) * f,
+        )
+    }
+
+    pub fn project_to_dominant_plane(&self) -> (Point2f, Int) {
+        let (ax, ay, az) = (self.x.abs(), self.y.abs(), self.z.abs());
+        if ax >= ay && ax >= az {
+            (Point2f::new(self.y, self.z), 0)
+        } else if ay >= az {
+            (Point2f::new(self.x, self.z), 1)
+        } else {
+            (Point2f::new(self.x, self.y), 2)
+        }
+    }
+
+    // the signed unit axis along the largest-magnitude component
+    pub fn snap_to_nearest_axis(&self) -> Vector3f {
+        let axis = self.abs().max_dimension();
+        let sign = if self[axis] < 0.0 { -1.0 } else { 1.0 };
+        match axis {
+            0 => Vector3f::new(sign, 0.0, 0.0),
+            1 => Vector3f::new(0.0, sign, 0.0),
+            _ => Vector3f::new(0.0, 0.0, sign),
+        }
+    }
+
+    // reciprocal direction plus the dir_is_neg flags the slab test indexes
+    // bounds with; the flag follows the reciprocal so -0.0 counts as negative
+    pub fn inv_and_signs(&self) -> (Vector3f, [usize; 3]) {
+        let inv = Vector3f::new(1.0 / self.x, 1.0 / self.y, 1.0 / self.z);
+        let neg = |v: Float| (v < 0.0) as usize;
+        (inv, [neg(inv.x), neg(inv.y), neg(inv.z)])
+    }
+}
+
+impl Dot for Vector3<Float> {
+    fn dot(&self, other: &Self) -> Float {
+        Vector3::dot(self, other)
+    }
 }
 
 impl<T: Scalar> HasNaN for Vector3<T> {
@@ -43,6 +233,20 @@ impl<T: Scalar> HasNaN for Vector3<T> {
     }
 }
 
+impl ApproxEq for Vector3<Float> {
+    fn approx_eq(&self, other: &Self, eps: Float) -> bool {
+        self.x.approx_eq(&other.x, eps)
+            && self.y.approx_eq(&other.y, eps)
+            && self.z.approx_eq(&other.z, eps)
+    }
+}
+
+impl Lerp for Vector3<Float> {
+    fn lerp(self, other: Self, t: Float) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
 impl<T: Scalar> PartialEq for Vector3<T> {
     fn eq(&self, rhs: &Vector3<T>) -> bool {
         self.x == rhs.x && self.y == rhs.y && self.z == rhs.z
@@ -135,12 +339,550 @@ impl<T: Scalar> Index<Int> for Vector3<T> {
     }
 }
 
+impl<T: Scalar + Display> Display for Vector3<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "[{}, {}, {}]", self.x, self.y, self.z)
+    }
+}
+
 pub type Vector3f = Vector3<Float>;
 pub type Vector3i = Vector3<i32>;
 
+pub fn cross(v1: &Vector3f, v2: &Vector3f) -> Vector3f {
+    Vector3f::new(
+        difference_of_products(v1.y, v2.z, v1.z, v2.y),
+        difference_of_products(v1.z, v2.x, v1.x, v2.z),
+        difference_of_products(v1.x, v2.y, v1.y, v2.x),
+    )
+}
+
+pub fn coordinate_system(v1: &Vector3f, v2: &mut Vector3f, v3: &mut Vector3f) {
+    *v2 = if v1.x.abs() > v1.y.abs() {
+        Vector3f::new(-v1.z, 0.0, v1.x) / (v1.x * v1.x + v1.z * v1.z).sqrt()
+    } else {
+        Vector3f::new(0.0, v1.z, -v1.y) / (v1.y * v1.y + v1.z * v1.z).sqrt()
+    };
+    *v3 = cross(v1, v2);
+}
+
+pub fn face_forward(n: &Vector3f, v: &Vector3f) -> Vector3f {
+    if n.dot(v) < 0.0 {
+        -*n
+    } else {
+        *n
+    }
+}
+
+// gram-schmidt of dp_du against n, with the bitangent completing the frame
+// on dp_dv's side; a zero gradient falls back to an arbitrary frame around n
+pub fn tangent_from_uv(dp_du: &Vector3f, dp_dv: &Vector3f, n: &Normal3f) -> (Vector3f, Vector3f) {
+    let n = Vector3f::from(*n).normalize();
+    let t = *dp_du - n * n.dot(dp_du);
+    if t.length_squared() == 0.0 {
+        let (mut t, mut b) = (Vector3f::default(), Vector3f::default());
+        coordinate_system(&n, &mut t, &mut b);
+        return (t, b);
+    }
+    let t = t.normalize();
+    let b = cross(&n, &t);
+    if b.dot(dp_dv) < 0.0 {
+        (t, -b)
+    } else {
+        (t, b)
+    }
+}
+
+// a . (b x c), the signed volume of the parallelepiped spanned by a, b and c
+pub fn scalar_triple(a: &Vector3f, b: &Vector3f, c: &Vector3f) -> Float {
+    a.dot(&cross(b, c))
+}
+
+// a x (b x c)
+pub fn vector_triple(a: &Vector3f, b: &Vector3f, c: &Vector3f) -> Vector3f {
+    cross(a, &cross(b, c))
+}
+
+// per-component mean; an empty slice yields the zero vector
+pub fn mean(vs: &[Vector3f]) -> Vector3f {
+    if vs.is_empty() {
+        return Vector3f::default();
+    }
+    let mut sum = Vector3f::default();
+    for v in vs {
+        sum += *v;
+    }
+    sum / vs.len() as Float
+}
+
+// per-component population variance; an empty slice yields the zero vector
+pub fn variance(vs: &[Vector3f]) -> Vector3f {
+    if vs.is_empty() {
+        return Vector3f::default();
+    }
+    let m = mean(vs);
+    let mut sum = Vector3f::default();
+    for v in vs {
+        let d = *v - m;
+        sum += Vector3f::new(d.x * d.x, d.y * d.y, d.z * d.z);
+    }
+    sum / vs.len() as Float
+}
+
+// central differences along each axis, O(eps^2) accurate for smooth f
+pub fn finite_diff_gradient<F: Fn(&Point3f) -> Float>(f: F, p: &Point3f, eps: Float) -> Vector3f {
+    debug_assert!(eps > 0.0);
+    let diff = |axis: Vector3f| (f(&(*p + axis * eps)) - f(&(*p - axis * eps))) / (2.0 * eps);
+    Vector3f::new(
+        diff(Vector3f::new(1.0, 0.0, 0.0)),
+        diff(Vector3f::new(0.0, 1.0, 0.0)),
+        diff(Vector3f::new(0.0, 0.0, 1.0)),
+    )
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct EmaVector3 {
+    pub value: Vector3f,
+    pub alpha: Float,
+}
+
+impl EmaVector3 {
+    pub fn new(value: Vector3f, alpha: Float) -> Self {
+        debug_assert!((0.0..=1.0).contains(&alpha));
+        Self { value, alpha }
+    }
+
+    pub fn update(&mut self, sample: &Vector3f) {
+        self.value = self.value.lerp(*sample, self.alpha);
+    }
+}
+
+pub fn find_nan_indices(vs: &[Vector3f]) -> Vec<usize> {
+    vs.iter()
+        .enumerate()
+        .filter(|(_, v)| v.has_nan())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+pub fn find_non_finite_indices(vs: &[Vector3f]) -> Vec<usize> {
+    vs.iter()
+        .enumerate()
+        .filter(|(_, v)| !(v.x.is_finite() && v.y.is_finite() && v.z.is_finite()))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// solid angle of the spherical triangle spanned by three unit vectors, using the
+// Van Oosterom-Strackee atan2 form which stays accurate for small triangles
+pub fn spherical_triangle_area(a: &Vector3f, b: &Vector3f, c: &Vector3f) -> Float {
+    let triple = a.x * (b.y * c.z - b.z * c.y)
+        + a.y * (b.z * c.x - b.x * c.z)
+        + a.z * (b.x * c.y - b.y * c.x);
+    let ab = a.x * b.x + a.y * b.y + a.z * b.z;
+    let bc = b.x * c.x + b.y * c.y + b.z * c.z;
+    let ca = c.x * a.x + c.y * a.y + c.z * a.z;
+    (2.0 * Float::atan2(triple, 1.0 + ab + bc + ca)).abs()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::pbrt::{Float, HasNaN};
+    use crate::pbrt::{Dot, Float, HasNaN, Point2f, Point3f};
+
+    #[test]
+    pub fn test_vector3_normalize() {
+        let v = super::Vector3f::new(3.0, 4.0, 0.0).normalize();
+        assert!((v.length() - 1.0).abs() < 1e-6);
+        assert!((v.x - 0.6).abs() < 1e-6);
+        assert!((v.y - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_vector3_normalize_zero() {
+        let v = super::Vector3f::new(0.0, 0.0, 0.0);
+        assert_eq!(v.normalize(), v);
+    }
+
+    #[test]
+    pub fn test_vector3i_normalize() {
+        let v = super::Vector3i::new(0, 0, 5).normalize();
+        assert_eq!(v, super::Vector3i::new(0, 0, 1));
+    }
+
+    #[test]
+    pub fn test_vector3_dot() {
+        let left = super::Vector3f::new(1.0, 2.0, 3.0);
+        let right = super::Vector3f::new(4.0, 5.0, 6.0);
+        assert_eq!(left.dot(&right), 32.0);
+        let left = super::Vector3i::new(1, 2, 3);
+        let right = super::Vector3i::new(4, 5, 6);
+        assert_eq!(left.dot(&right), 32);
+    }
+
+    #[test]
+    pub fn test_vector3_abs_dot() {
+        let left = super::Vector3f::new(1.0, 2.0, 3.0);
+        let right = -left;
+        assert_eq!(left.dot(&right), -14.0);
+        assert_eq!(left.abs_dot(&right), 14.0);
+        assert_eq!(
+            super::Vector3i::new(1, 0, 0).abs_dot(&super::Vector3i::new(-2, 0, 0)),
+            2
+        );
+    }
+
+    #[test]
+    pub fn test_vector3_min_max_component() {
+        let v = super::Vector3f::new(-1.0, 4.0, -3.0);
+        assert_eq!(v.min_component(), -3.0);
+        assert_eq!(v.max_component(), 4.0);
+        assert_eq!(v.min_dimension(), 2);
+        assert_eq!(v.max_dimension(), 1);
+    }
+
+    #[test]
+    pub fn test_vector3_dimension_ties() {
+        let v = super::Vector3i::new(2, 2, 2);
+        assert_eq!(v.min_dimension(), 0);
+        assert_eq!(v.max_dimension(), 0);
+        let v = super::Vector3i::new(5, -1, -1);
+        assert_eq!(v.min_dimension(), 1);
+        let v = super::Vector3i::new(-5, 3, 3);
+        assert_eq!(v.max_dimension(), 1);
+    }
+
+    #[test]
+    pub fn test_vector3_min_max() {
+        let left = super::Vector3f::new(1.0, -5.0, 3.0);
+        let right = super::Vector3f::new(-4.0, 2.0, 3.0);
+        assert_eq!(
+            super::Vector3f::min(&left, &right),
+            super::Vector3f::new(-4.0, -5.0, 3.0)
+        );
+        assert_eq!(
+            super::Vector3f::max(&left, &right),
+            super::Vector3f::new(1.0, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    pub fn test_vector3_permute() {
+        let v = super::Vector3f::new(1.0, 2.0, 3.0);
+        assert_eq!(v.permute(2, 0, 1), super::Vector3f::new(3.0, 1.0, 2.0));
+        assert_eq!(v.permute(0, 0, 0), super::Vector3f::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    pub fn test_vector3_abs() {
+        let v = super::Vector3f::new(-1.0, 2.0, -3.0);
+        assert_eq!(v.abs(), super::Vector3f::new(1.0, 2.0, 3.0));
+        let v = super::Vector3i::new(-1, 0, 7);
+        assert_eq!(v.abs(), super::Vector3i::new(1, 0, 7));
+    }
+
+    #[test]
+    pub fn test_vector3_dot_trait() {
+        let left = super::Vector3f::new(1.0, 2.0, 3.0);
+        let right = super::Vector3f::new(4.0, -5.0, 6.0);
+        assert_eq!(Dot::dot(&left, &right), 12.0);
+    }
+
+    #[test]
+    pub fn test_vector3_eq_ignoring_x() {
+        let left = super::Vector3f::new(1.0, 2.0, 3.0);
+        let right = super::Vector3f::new(9.0, 2.0, 3.0 + 1e-6);
+        assert!(left.eq_ignoring_axis(&right, 0, 1e-5));
+        assert!(!left.eq_ignoring_axis(&right, 1, 1e-5));
+        assert!(!left.eq_ignoring_axis(&right, 2, 1e-5));
+    }
+
+    #[test]
+    pub fn test_vector3_eq_ignoring_y() {
+        let left = super::Vector3f::new(1.0, 2.0, 3.0);
+        let right = super::Vector3f::new(1.0, -7.0, 3.0);
+        assert!(left.eq_ignoring_axis(&right, 1, 1e-5));
+        assert!(!left.eq_ignoring_axis(&right, 0, 1e-5));
+    }
+
+    #[test]
+    pub fn test_vector3_eq_ignoring_z() {
+        let left = super::Vector3f::new(1.0, 2.0, 3.0);
+        let right = super::Vector3f::new(1.0, 2.0, 100.0);
+        assert!(left.eq_ignoring_axis(&right, 2, 1e-5));
+        assert!(!left.eq_ignoring_axis(&right, 0, 1e-5));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_vector3_eq_ignoring_axis_panic() {
+        let left = super::Vector3f::new(1.0, 2.0, 3.0);
+        left.eq_ignoring_axis(&left, 3, 1e-5);
+    }
+
+    #[test]
+    pub fn test_vector3_bucket_ends() {
+        let v = super::Vector3f::new(0.0, 1.0, 0.999);
+        assert_eq!(v.bucket(4, (0.0, 1.0)), [0, 3, 3]);
+    }
+
+    #[test]
+    pub fn test_vector3_bucket_clamp() {
+        let v = super::Vector3f::new(-5.0, 5.0, Float::INFINITY);
+        assert_eq!(v.bucket(4, (0.0, 1.0)), [0, 3, 3]);
+    }
+
+    #[test]
+    pub fn test_vector3_bucket_middle() {
+        let v = super::Vector3f::new(0.5, 0.4, 0.6);
+        assert_eq!(v.bucket(3, (0.0, 1.0)), [1, 1, 1]);
+    }
+
+    #[test]
+    pub fn test_vector3_project_to_dominant_plane() {
+        let (p, axis) = super::Vector3f::new(-5.0, 1.0, 2.0).project_to_dominant_plane();
+        assert_eq!(axis, 0);
+        assert_eq!(p, Point2f::new(1.0, 2.0));
+        let (p, axis) = super::Vector3f::new(1.0, 3.0, -2.0).project_to_dominant_plane();
+        assert_eq!(axis, 1);
+        assert_eq!(p, Point2f::new(1.0, -2.0));
+        let (p, axis) = super::Vector3f::new(1.0, 2.0, -7.0).project_to_dominant_plane();
+        assert_eq!(axis, 2);
+        assert_eq!(p, Point2f::new(1.0, 2.0));
+    }
+
+    #[test]
+    pub fn test_vector3_scrub_nan() {
+        let v = super::Vector3f {
+            x: Float::NAN,
+            y: Float::INFINITY,
+            z: Float::NEG_INFINITY,
+        };
+        assert_eq!(v.scrub_nan(0.0), super::Vector3f::new(0.0, 0.0, 0.0));
+        assert_eq!(v.scrub_nan(2.0), super::Vector3f::new(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    pub fn test_vector3_scrub_nan_clean() {
+        let v = super::Vector3f::new(1.0, -2.0, 3.0);
+        assert_eq!(v.scrub_nan(0.0), v);
+    }
+
+    #[test]
+    pub fn test_vector3_quantize() {
+        let v = super::Vector3f::new(0.9, -2.1, 0.2);
+        assert_eq!(v.quantize(1.0), super::Vector3f::new(1.0, -2.0, 0.0));
+    }
+
+    #[test]
+    pub fn test_mean_variance() {
+        let vs = [
+            super::Vector3f::new(1.0, 2.0, 0.0),
+            super::Vector3f::new(3.0, 2.0, 0.0),
+            super::Vector3f::new(5.0, 2.0, 6.0),
+            super::Vector3f::new(7.0, 2.0, 2.0),
+        ];
+        assert_eq!(super::mean(&vs), super::Vector3f::new(4.0, 2.0, 2.0));
+        assert_eq!(super::variance(&vs), super::Vector3f::new(5.0, 0.0, 6.0));
+    }
+
+    #[test]
+    pub fn test_mean_variance_empty() {
+        assert_eq!(super::mean(&[]), super::Vector3f::default());
+        assert_eq!(super::variance(&[]), super::Vector3f::default());
+    }
+
+    #[test]
+    pub fn test_vector3_hash_key() {
+        let v = super::Vector3f::new(0.0, 1.0, 2.0);
+        let w = super::Vector3f::new(-0.0, 1.0, 2.0);
+        assert_eq!(v.hash_key(), w.hash_key());
+        assert_ne!(v.hash_key(), super::Vector3f::new(1.0, 0.0, 2.0).hash_key());
+        assert_ne!(v.hash_key(), super::Vector3f::new(0.0, 2.0, 1.0).hash_key());
+    }
+
+    #[test]
+    pub fn test_ema_first_update() {
+        let mut ema = super::EmaVector3::new(super::Vector3f::default(), 0.25);
+        ema.update(&super::Vector3f::new(4.0, 8.0, -4.0));
+        assert_eq!(ema.value, super::Vector3f::new(1.0, 2.0, -1.0));
+    }
+
+    #[test]
+    pub fn test_ema_converges() {
+        let sample = super::Vector3f::new(1.0, 2.0, 3.0);
+        let mut ema = super::EmaVector3::new(super::Vector3f::default(), 0.5);
+        for _ in 0..64 {
+            ema.update(&sample);
+        }
+        assert!((ema.value - sample).length() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_find_nan_indices() {
+        let vs = [
+            super::Vector3f::new(1.0, 2.0, 3.0),
+            super::Vector3f {
+                x: 0.0,
+                y: Float::NAN,
+                z: 0.0,
+            },
+            super::Vector3f {
+                x: Float::INFINITY,
+                y: 0.0,
+                z: 0.0,
+            },
+            super::Vector3f::new(4.0, 5.0, 6.0),
+            super::Vector3f {
+                x: 0.0,
+                y: 0.0,
+                z: Float::NAN,
+            },
+            super::Vector3f {
+                x: 0.0,
+                y: Float::NEG_INFINITY,
+                z: 0.0,
+            },
+        ];
+        assert_eq!(super::find_nan_indices(&vs), vec![1, 4]);
+        assert_eq!(super::find_non_finite_indices(&vs), vec![1, 2, 4, 5]);
+        assert!(super::find_nan_indices(&vs[..1]).is_empty());
+    }
+
+    #[test]
+    pub fn test_vector3_rgbe_black() {
+        assert_eq!(super::Vector3f::default().to_rgbe(), [0, 0, 0, 0]);
+        assert_eq!(
+            super::Vector3f::from_rgbe(&[0, 0, 0, 0]),
+            super::Vector3f::default()
+        );
+    }
+
+    #[test]
+    pub fn test_vector3_rgbe_round_trip() {
+        for c in &[
+            super::Vector3f::new(1.0, 0.5, 0.25),
+            super::Vector3f::new(0.01, 0.02, 0.03),
+            super::Vector3f::new(1000.0, 250.0, 900.0),
+            super::Vector3f::new(1.0e6, 3.0e5, 0.0),
+        ] {
+            let back = super::Vector3f::from_rgbe(&c.to_rgbe());
+            let max = c.max_component();
+            // 8-bit mantissas relative to the largest component
+            assert!((back - *c).abs().max_component() <= max / 128.0);
+        }
+    }
+
+    #[test]
+    pub fn test_vector3_rgbe_exponent() {
+        let rgbe = super::Vector3f::new(1.0, 0.5, 0.0).to_rgbe();
+        assert_eq!(rgbe, [128, 64, 0, 129]);
+    }
+
+    #[test]
+    pub fn test_tangent_from_uv() {
+        let n = super::Normal3f::new(0.0, 0.0, 1.0);
+        let dp_du = super::Vector3f::new(2.0, 0.1, 0.5);
+        let dp_dv = super::Vector3f::new(0.0, -1.0, 0.2);
+        let (t, b) = super::tangent_from_uv(&dp_du, &dp_dv, &n);
+        let nv = super::Vector3f::from(n);
+        assert!(t.dot(&nv).abs() < 1e-6);
+        assert!(b.dot(&nv).abs() < 1e-6);
+        assert!(t.dot(&b).abs() < 1e-6);
+        assert!(t.dot(&dp_du.normalize()) > 0.9);
+        assert!(b.dot(&dp_dv) > 0.0);
+    }
+
+    #[test]
+    pub fn test_tangent_from_uv_degenerate() {
+        let n = super::Normal3f::new(0.0, 1.0, 0.0);
+        let zero = super::Vector3f::default();
+        let (t, b) = super::tangent_from_uv(&zero, &zero, &n);
+        let nv = super::Vector3f::from(n);
+        assert!((t.length() - 1.0).abs() < 1e-6);
+        assert!((b.length() - 1.0).abs() < 1e-6);
+        assert!(t.dot(&nv).abs() < 1e-6);
+        assert!(b.dot(&nv).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_cross() {
+        let x = super::Vector3f::new(1.0, 0.0, 0.0);
+        let y = super::Vector3f::new(0.0, 1.0, 0.0);
+        assert_eq!(super::cross(&x, &y), super::Vector3f::new(0.0, 0.0, 1.0));
+        assert_eq!(super::cross(&y, &x), super::Vector3f::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    pub fn test_coordinate_system() {
+        for v1 in &[
+            super::Vector3f::new(1.0, 2.0, 3.0).normalize(),
+            super::Vector3f::new(-3.0, 0.5, 0.2).normalize(),
+            super::Vector3f::new(0.0, 0.0, 1.0),
+        ] {
+            let mut v2 = super::Vector3f::default();
+            let mut v3 = super::Vector3f::default();
+            super::coordinate_system(v1, &mut v2, &mut v3);
+            assert!(v1.dot(&v2).abs() < 1e-6);
+            assert!(v1.dot(&v3).abs() < 1e-6);
+            assert!(v2.dot(&v3).abs() < 1e-6);
+            assert!((v2.length() - 1.0).abs() < 1e-6);
+            assert!((v3.length() - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    pub fn test_face_forward() {
+        let n = super::Vector3f::new(0.0, 0.0, 1.0);
+        let v = super::Vector3f::new(0.0, 0.0, -1.0);
+        assert_eq!(
+            super::face_forward(&n, &v),
+            super::Vector3f::new(0.0, 0.0, -1.0)
+        );
+        let v = super::Vector3f::new(0.3, 0.0, 1.0);
+        assert_eq!(super::face_forward(&n, &v), n);
+    }
+
+    #[test]
+    pub fn test_scalar_triple_unit_cube() {
+        let x = super::Vector3f::new(1.0, 0.0, 0.0);
+        let y = super::Vector3f::new(0.0, 1.0, 0.0);
+        let z = super::Vector3f::new(0.0, 0.0, 1.0);
+        assert_eq!(super::scalar_triple(&x, &y, &z), 1.0);
+        assert_eq!(super::scalar_triple(&x, &z, &y), -1.0);
+    }
+
+    #[test]
+    pub fn test_vector_triple_bac_cab() {
+        let a = super::Vector3f::new(1.0, 2.0, 3.0);
+        let b = super::Vector3f::new(-2.0, 0.5, 4.0);
+        let c = super::Vector3f::new(3.0, -1.0, 2.0);
+        let expected = b * a.dot(&c) - c * a.dot(&b);
+        let result = super::vector_triple(&a, &b, &c);
+        assert!((result - expected).length() < 1e-4);
+    }
+
+    #[test]
+    pub fn test_spherical_triangle_area_octant() {
+        let a = super::Vector3f::new(1.0, 0.0, 0.0);
+        let b = super::Vector3f::new(0.0, 1.0, 0.0);
+        let c = super::Vector3f::new(0.0, 0.0, 1.0);
+        let area = super::spherical_triangle_area(&a, &b, &c);
+        assert!((area - core::f64::consts::FRAC_PI_2 as Float).abs() < 1e-6);
+        let area = super::spherical_triangle_area(&a, &c, &b);
+        assert!((area - core::f64::consts::FRAC_PI_2 as Float).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_spherical_triangle_area_tiny() {
+        // nearly planar triangle around +z, area approaches the flat triangle area
+        let e: Float = 1e-3;
+        let a = super::Vector3f::new(0.0, 0.0, 1.0);
+        let b = super::Vector3f::new(e, 0.0, 1.0) / super::Vector3f::new(e, 0.0, 1.0).length();
+        let c = super::Vector3f::new(0.0, e, 1.0) / super::Vector3f::new(0.0, e, 1.0).length();
+        let area = super::spherical_triangle_area(&a, &b, &c);
+        let flat = 0.5 * e * e;
+        assert!(area > 0.0);
+        assert!((area - flat).abs() / flat < 1e-2);
+    }
 
     #[test]
     pub fn tst_vector3_chain() {
@@ -220,6 +962,13 @@ mod tests {
         assert!(left == right);
     }
 
+    #[test]
+    pub fn test_vector3_neq_z() {
+        let left = super::Vector3f::new(1.0, 2.0, 4.0);
+        let right = super::Vector3f::new(1.0, 2.0, 5.0);
+        assert!(left != right);
+    }
+
     #[test]
     pub fn test_vector3f_default() {
         let vec3f = super::Vector3f::default();
@@ -303,4 +1052,82 @@ mod tests {
         assert_eq!(neg.y, -6.0);
         assert_eq!(neg.z, -9.0);
     }
+
+    #[test]
+    pub fn test_snap_to_nearest_axis() {
+        let v = super::Vector3f::new(0.2, 0.9, -0.3);
+        assert_eq!(
+            v.snap_to_nearest_axis(),
+            super::Vector3f::new(0.0, 1.0, 0.0)
+        );
+        let v = super::Vector3f::new(-0.6, 0.5, 0.55);
+        assert_eq!(
+            v.snap_to_nearest_axis(),
+            super::Vector3f::new(-1.0, 0.0, 0.0)
+        );
+        let v = super::Vector3f::new(0.1, -0.1, -2.0);
+        assert_eq!(
+            v.snap_to_nearest_axis(),
+            super::Vector3f::new(0.0, 0.0, -1.0)
+        );
+    }
+
+    #[test]
+    pub fn test_vector3_display() {
+        assert_eq!(
+            format!("{}", super::Vector3f::new(1.0, 2.0, 3.0)),
+            "[1, 2, 3]"
+        );
+    }
+
+    #[test]
+    pub fn test_inv_and_signs_octants() {
+        for octant in 0..8 {
+            let sx = if octant & 1 != 0 { -1.0 } else { 1.0 };
+            let sy = if octant & 2 != 0 { -1.0 } else { 1.0 };
+            let sz = if octant & 4 != 0 { -1.0 } else { 1.0 };
+            let d = super::Vector3f::new(2.0 * sx, 4.0 * sy, 0.5 * sz);
+            let (inv, signs) = d.inv_and_signs();
+            assert_eq!(inv, super::Vector3f::new(0.5 * sx, 0.25 * sy, 2.0 * sz));
+            assert_eq!(
+                signs,
+                [
+                    (octant & 1 != 0) as usize,
+                    (octant & 2 != 0) as usize,
+                    (octant & 4 != 0) as usize
+                ]
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_inv_and_signs_zero() {
+        let (inv, signs) = super::Vector3f::new(0.0, -0.0, 1.0).inv_and_signs();
+        assert_eq!(inv.x, Float::INFINITY);
+        assert_eq!(inv.y, Float::NEG_INFINITY);
+        assert_eq!(signs, [0, 1, 0]);
+    }
+
+    #[test]
+    pub fn test_finite_diff_gradient() {
+        // f = x^2 + 3xy - 2z^2 + z, grad = (2x + 3y, 3x, 1 - 4z)
+        let f = |p: &Point3f| p.x * p.x + 3.0 * p.x * p.y - 2.0 * p.z * p.z + p.z;
+        let p = Point3f::new(1.0, -2.0, 0.5);
+        let g = super::finite_diff_gradient(f, &p, 1e-2);
+        let expected = super::Vector3f::new(2.0 * 1.0 + 3.0 * -2.0, 3.0 * 1.0, 1.0 - 4.0 * 0.5);
+        assert!((g - expected).length() < 1e-3, "{:?} != {:?}", g, expected);
+    }
+
+    #[test]
+    pub fn test_vector3_approx_eq() {
+        use crate::pbrt::ApproxEq;
+        let exact = super::Vector3f::new(1.0, 0.1, 3.0);
+        let nudged = super::Vector3f::new(1.0, 0.1 + 1e-7, 3.0);
+        assert!(exact != nudged);
+        assert!(exact.approx_eq(&nudged, 1e-6));
+        assert!(!exact.approx_eq(&nudged, 1e-8));
+        let a = super::Vector3f::new(1.0, 2.0, 3.0);
+        assert!(a.approx_eq(&super::Vector3f::new(1.0, 2.0, 3.01), 0.1));
+        assert!(!a.approx_eq(&super::Vector3f::new(1.0, 2.0, 3.5), 0.1));
+    }
 }
@@ -3,3 +3,15 @@ pub use self::geometry::*;
 
 mod pbrt;
 pub use self::pbrt::*;
+
+mod reflection;
+pub use self::reflection::*;
+
+mod sampling;
+pub use self::sampling::*;
+
+mod float_util;
+pub use self::float_util::*;
+
+mod efloat;
+pub use self::efloat::*;
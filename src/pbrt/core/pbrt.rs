@@ -9,6 +9,8 @@ use core::ops::Sub;
 use core::ops::SubAssign;
 use std::ops::DivAssign;
 
+use crate::pbrt::clamp;
+
 #[cfg(PBRT_FLOAT_AS_DOUBLE)]
 pub type Float = f64;
 
@@ -17,6 +19,35 @@ pub type Float = f32;
 
 pub type Int = i32;
 
+pub const PI: Float = std::f64::consts::PI as Float;
+pub const INV_PI: Float = std::f64::consts::FRAC_1_PI as Float;
+pub const INV_4_PI: Float = (0.25 * std::f64::consts::FRAC_1_PI) as Float;
+pub const PI_OVER_2: Float = std::f64::consts::FRAC_PI_2 as Float;
+pub const PI_OVER_4: Float = std::f64::consts::FRAC_PI_4 as Float;
+
+#[inline]
+pub fn mix_bits(mut v: u64) -> u64 {
+    v ^= v >> 31;
+    v = v.wrapping_mul(0x7fb5d329728ea185);
+    v ^= v >> 27;
+    v = v.wrapping_mul(0x81dadef4bc2dd44d);
+    v ^= v >> 33;
+    v
+}
+
+// -0.0 and +0.0 hash equally, as do all NaNs
+#[inline]
+pub fn hash_float(v: Float) -> u64 {
+    let canonical = if v == 0.0 {
+        0.0
+    } else if v.is_nan() {
+        Float::NAN
+    } else {
+        v
+    };
+    mix_bits(canonical.to_bits() as u64)
+}
+
 pub trait HasNaN {
     fn has_nan(&self) -> bool;
 }
@@ -35,6 +66,42 @@ impl HasNaN for Int {
     }
 }
 
+pub trait Dot {
+    fn dot(&self, other: &Self) -> Float;
+}
+
+// component-wise comparison within an absolute tolerance, for computed
+// geometry where exact == is too strict
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self, eps: Float) -> bool;
+}
+
+impl ApproxEq for Float {
+    #[inline]
+    fn approx_eq(&self, other: &Self, eps: Float) -> bool {
+        // the equality check lets matching infinities through, whose
+        // difference is NaN
+        self == other || (self - other).abs() <= eps
+    }
+}
+
+// blend from self at t = 0 to other at t = 1
+pub trait Lerp {
+    fn lerp(self, other: Self, t: Float) -> Self;
+}
+
+impl Lerp for Float {
+    #[inline]
+    fn lerp(self, other: Self, t: Float) -> Self {
+        (1.0 - t) * self + t * other
+    }
+}
+
+pub trait BoundsAxes<T> {
+    fn axes(&self) -> Int;
+    fn extent(&self, axis: Int) -> T;
+}
+
 pub trait Zero {
     fn zero() -> Self;
 }
@@ -186,8 +253,86 @@ pub trait Scalar:
     + Max
     + PartialOrd
 {
+    // for clamping negative cosines and the like
+    #[inline]
+    fn max_zero(self) -> Self {
+        if self > Self::zero() {
+            self
+        } else {
+            Self::zero()
+        }
+    }
+
+    // same as utils::clamp: never panics, and NaN passes through. it has to
+    // be called as Scalar::clamp, since method syntax on Float and Int finds
+    // the inherent f32::clamp and Ord::clamp first, which panic if lo > hi
+    #[inline]
+    fn clamp(self, lo: Self, hi: Self) -> Self {
+        clamp(self, lo, hi)
+    }
 }
 
 impl Scalar for Int {}
 
 impl Scalar for Float {}
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::{ApproxEq, Float, Lerp, Scalar};
+
+    #[test]
+    pub fn test_scalar_max_zero() {
+        assert_eq!(Scalar::max_zero(-3), 0);
+        assert_eq!(Scalar::max_zero(4), 4);
+        assert_eq!((-0.5 as Float).max_zero(), 0.0);
+        assert_eq!((2.5 as Float).max_zero(), 2.5);
+    }
+
+    #[test]
+    pub fn test_scalar_clamp() {
+        assert_eq!(Scalar::clamp(5, 0, 3), 3);
+        assert_eq!(Scalar::clamp(-5, 0, 3), 0);
+        assert_eq!(Scalar::clamp(2, 0, 3), 2);
+        assert_eq!(Scalar::clamp(1.5 as Float, 0.0, 1.0), 1.0);
+        assert_eq!(Scalar::clamp(-1.5 as Float, 0.0, 1.0), 0.0);
+    }
+
+    #[test]
+    pub fn test_float_lerp() {
+        let a: Float = 2.0;
+        assert_eq!(a.lerp(6.0, 0.0), 2.0);
+        assert_eq!(a.lerp(6.0, 0.25), 3.0);
+        assert_eq!(a.lerp(6.0, 1.0), 6.0);
+    }
+
+    #[test]
+    pub fn test_float_approx_eq() {
+        let a: Float = 1.0;
+        assert!(a.approx_eq(&(1.0 + 1e-7), 1e-6));
+        assert!(!a.approx_eq(&1.1, 1e-6));
+        assert!(Float::INFINITY.approx_eq(&Float::INFINITY, 1e-6));
+        assert!(!Float::INFINITY.approx_eq(&Float::NEG_INFINITY, 1e-6));
+        assert!(!Float::INFINITY.approx_eq(&Float::MAX, 1e-6));
+        assert!(!Float::NAN.approx_eq(&Float::NAN, 1e-6));
+        assert!(!a.approx_eq(&Float::NAN, Float::INFINITY));
+    }
+
+    #[test]
+    pub fn test_hash_float_signed_zero() {
+        assert_eq!(super::hash_float(0.0), super::hash_float(-0.0));
+    }
+
+    #[test]
+    pub fn test_hash_float_nan() {
+        assert_eq!(
+            super::hash_float(Float::NAN),
+            super::hash_float(-Float::NAN)
+        );
+    }
+
+    #[test]
+    pub fn test_hash_float_distinct() {
+        assert_ne!(super::hash_float(1.0), super::hash_float(2.0));
+        assert_ne!(super::hash_float(1.0), super::hash_float(-1.0));
+    }
+}
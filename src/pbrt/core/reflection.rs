@@ -0,0 +1,58 @@
+use crate::pbrt::Float;
+
+// unpolarized Fresnel reflectance between two dielectrics; a negative cosine
+// means the ray arrives from the eta_t side, so the indices are swapped
+pub fn fresnel_dielectric(cos_theta_i: Float, eta_i: Float, eta_t: Float) -> Float {
+    let mut cos_theta_i = cos_theta_i.clamp(-1.0, 1.0);
+    let (eta_i, eta_t) = if cos_theta_i > 0.0 {
+        (eta_i, eta_t)
+    } else {
+        cos_theta_i = cos_theta_i.abs();
+        (eta_t, eta_i)
+    };
+
+    let sin_theta_i = (1.0 - cos_theta_i * cos_theta_i).max(0.0).sqrt();
+    let sin_theta_t = eta_i / eta_t * sin_theta_i;
+    // total internal reflection
+    if sin_theta_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_theta_t = (1.0 - sin_theta_t * sin_theta_t).max(0.0).sqrt();
+
+    let r_parl = ((eta_t * cos_theta_i) - (eta_i * cos_theta_t))
+        / ((eta_t * cos_theta_i) + (eta_i * cos_theta_t));
+    let r_perp = ((eta_i * cos_theta_i) - (eta_t * cos_theta_t))
+        / ((eta_i * cos_theta_i) + (eta_t * cos_theta_t));
+    (r_parl * r_parl + r_perp * r_perp) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::Float;
+
+    #[test]
+    pub fn test_fresnel_normal_incidence() {
+        let r0 = ((1.0 - 1.5) / (1.0 + 1.5) as Float).powi(2);
+        assert!((super::fresnel_dielectric(1.0, 1.0, 1.5) - r0).abs() < 1e-6);
+        // arriving from inside the glass swaps the indices, same reflectance head-on
+        assert!((super::fresnel_dielectric(-1.0, 1.0, 1.5) - r0).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_fresnel_critical_angle() {
+        let critical = (1.0 / 1.5 as Float).asin();
+        let past = (critical + 1e-3).cos();
+        let before = (critical - 1e-3).cos();
+        assert_eq!(super::fresnel_dielectric(past, 1.5, 1.0), 1.0);
+        let r = super::fresnel_dielectric(before, 1.5, 1.0);
+        assert!(r < 1.0 && r > 0.5);
+    }
+
+    #[test]
+    pub fn test_fresnel_clamps_cosine() {
+        assert_eq!(
+            super::fresnel_dielectric(2.0, 1.0, 1.5),
+            super::fresnel_dielectric(1.0, 1.0, 1.5)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::pbrt::{Float, Point2f, Vector3f, INV_4_PI, INV_PI, PI, PI_OVER_2, PI_OVER_4};
+
+// Shirley's concentric square-to-disk mapping, which keeps strata compact
+// and areas proportional unlike the naive polar mapping
+pub fn concentric_sample_disk(u: &Point2f) -> Point2f {
+    let u_offset = Point2f::new(2.0 * u.x - 1.0, 2.0 * u.y - 1.0);
+    if u_offset.x == 0.0 && u_offset.y == 0.0 {
+        return Point2f::new(0.0, 0.0);
+    }
+    let (r, theta) = if u_offset.x.abs() > u_offset.y.abs() {
+        (u_offset.x, PI_OVER_4 * (u_offset.y / u_offset.x))
+    } else {
+        (
+            u_offset.y,
+            PI_OVER_2 - PI_OVER_4 * (u_offset.x / u_offset.y),
+        )
+    };
+    Point2f::new(r * theta.cos(), r * theta.sin())
+}
+
+// Malley's method: uniform disk samples lifted onto the hemisphere
+pub fn cosine_sample_hemisphere(u: &Point2f) -> Vector3f {
+    let d = concentric_sample_disk(u);
+    let z = (1.0 - d.x * d.x - d.y * d.y).max(0.0).sqrt();
+    Vector3f::new(d.x, d.y, z)
+}
+
+#[inline]
+pub fn cosine_hemisphere_pdf(cos_theta: Float) -> Float {
+    cos_theta * INV_PI
+}
+
+pub fn uniform_sample_sphere(u: &Point2f) -> Vector3f {
+    let z = 1.0 - 2.0 * u.x;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * u.y;
+    Vector3f::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+#[inline]
+pub fn uniform_sphere_pdf() -> Float {
+    INV_4_PI
+}
+
+// barycentrics (b0, b1) of a uniformly distributed point; b2 = 1 - b0 - b1
+pub fn uniform_sample_triangle(u: &Point2f) -> (Float, Float) {
+    let su0 = u.x.sqrt();
+    (1.0 - su0, u.y * su0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pbrt::{Float, Point2f, INV_PI, PI};
+
+    fn grid(n: usize) -> impl Iterator<Item = Point2f> {
+        (0..n).flat_map(move |i| {
+            (0..n).map(move |j| {
+                Point2f::new(
+                    (i as Float + 0.5) / n as Float,
+                    (j as Float + 0.5) / n as Float,
+                )
+            })
+        })
+    }
+
+    #[test]
+    pub fn test_concentric_sample_disk_center() {
+        let d = super::concentric_sample_disk(&Point2f::new(0.5, 0.5));
+        assert_eq!(d, Point2f::new(0.0, 0.0));
+    }
+
+    #[test]
+    pub fn test_concentric_sample_disk_corners() {
+        for u in &[
+            Point2f::new(0.0, 0.0),
+            Point2f::new(1.0, 0.0),
+            Point2f::new(0.0, 1.0),
+            Point2f::new(1.0, 1.0),
+        ] {
+            let d = super::concentric_sample_disk(u);
+            let r = (d.x * d.x + d.y * d.y).sqrt();
+            assert!(r <= 1.0 + 1e-6);
+            // the square's corners land on the rim
+            assert!((r - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    pub fn test_concentric_sample_disk_radius() {
+        for u in grid(32) {
+            let d = super::concentric_sample_disk(&u);
+            assert!(d.x * d.x + d.y * d.y <= 1.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    pub fn test_cosine_sample_hemisphere() {
+        for u in grid(16) {
+            let w = super::cosine_sample_hemisphere(&u);
+            assert!(w.z >= 0.0);
+            assert!((w.length() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    pub fn test_cosine_hemisphere_pdf() {
+        assert!((super::cosine_hemisphere_pdf(1.0) - INV_PI).abs() < 1e-7);
+        assert_eq!(super::cosine_hemisphere_pdf(0.0), 0.0);
+        let half = super::cosine_hemisphere_pdf((PI / 3.0).cos());
+        assert!((half - 0.5 * INV_PI).abs() < 1e-6);
+        // integrating pdf * sin(theta) over theta and phi gives one
+        let n = 1000;
+        let d_theta = 0.5 * PI / n as Float;
+        let integral: Float = (0..n)
+            .map(|i| {
+                let theta = (i as Float + 0.5) * d_theta;
+                super::cosine_hemisphere_pdf(theta.cos()) * theta.sin() * d_theta
+            })
+            .sum::<Float>()
+            * 2.0
+            * PI;
+        assert!((integral - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    pub fn test_uniform_sample_sphere() {
+        let mut upper = 0;
+        for u in grid(16) {
+            let w = super::uniform_sample_sphere(&u);
+            assert!((w.length() - 1.0).abs() < 1e-5);
+            if w.z > 0.0 {
+                upper += 1;
+            }
+        }
+        assert_eq!(upper, 16 * 16 / 2);
+        assert!((super::uniform_sphere_pdf() * 4.0 * PI - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    pub fn test_uniform_sample_triangle() {
+        for u in grid(16).chain(vec![Point2f::new(0.0, 0.0), Point2f::new(1.0, 1.0)]) {
+            let (b0, b1) = super::uniform_sample_triangle(&u);
+            assert!(b0 >= 0.0 && b1 >= 0.0);
+            assert!(b0 + b1 <= 1.0 + 1e-6);
+        }
+    }
+}